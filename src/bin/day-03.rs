@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::iter::Iterator;
 use aoc2023::common::{read_input_lines};
-use aoc2023::coord::Pt;
+use aoc2023::coord::{Pt, Point};
 use aoc2023::grid::Grid;
 
 fn is_symbol(c: char) -> bool {
@@ -34,7 +34,7 @@ impl Span {
 }
 
 fn fill_digits(start: &Pt<usize>, grid: &Grid<u8>) -> Span {
-    let Pt(x, y) = start;
+    let Point([x, y]) = start;
     let mut span = Span { left: *x, len: 1, y: *y };
     // is there a nicer way of doing these loops?!
     // extend left as far as possible
@@ -61,8 +61,8 @@ fn connected_to(starts: impl Iterator<Item=Pt<usize>>, grid: &Grid<u8>) -> HashM
     let mut result = HashMap::new();
     for start in starts {
         let mut spans: Vec<Span> = Vec::new();
-        for neighbour in start.neighbours8() {
-            if !grid.contains(neighbour) || !grid[neighbour].is_ascii_digit() { continue; }
+        for (neighbour, c) in grid.neighbours8(start) {
+            if !c.is_ascii_digit() { continue; }
 
             let span = fill_digits(&neighbour, grid);
             if spans.is_empty() || !spans.last().unwrap().overlaps(&span) {
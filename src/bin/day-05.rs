@@ -1,83 +1,21 @@
-use std::cmp::{min};
-use itertools::{Itertools};
+use itertools::Itertools;
 use aoc2023::common::{read_input_lines, strs_to_nums};
+use aoc2023::interval::PiecewiseMap;
 
-struct RangeMap {
-    // Ranges stored as in the input (dst_start, src_start, len)
-    storage: Vec<(usize, usize, usize)>,
-    min_mapped: usize,
-}
-
-impl RangeMap {
-    fn from_lines(lines: &mut impl Iterator<Item=String>) -> Self {
-        let mut result = Self{storage: vec![], min_mapped: usize::MAX};
-        for line in lines.by_ref() {
-            if line.is_empty() {
-                break;
-            }
-            if let Some(tuple) = strs_to_nums(line.split_ascii_whitespace()).collect_tuple() {
-                result.storage.push(tuple);
-                result.min_mapped = min(tuple.1, result.min_mapped);
-            } else {
-                panic!("{} is malformed", line);
-            }
+fn map_from_lines(lines: &mut impl Iterator<Item=String>) -> PiecewiseMap<usize> {
+    let mut result = PiecewiseMap::new();
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
         }
-        result.storage.sort_by(|(_, source_start1, _), (_, source_start2, _)| source_start1.cmp(source_start2));
-        result
-    }
-
-    fn apply_to_range(&self, range: (usize, usize)) -> Vec<(usize, usize)> {
-        let mut result = vec![];
-        let (start, len) = range;
-        let end = start + len - 1;
-        let (next_boundary, offset) = self.get_next_boundary(start);
-        let next_boundary = next_boundary.unwrap_or(end);
-
-        if end <= next_boundary {
-            result.push(((start as isize + offset) as usize, len));
+        // as in the input: (dst_start, src_start, len)
+        if let Some((dest, source, len)) = strs_to_nums(line.split_ascii_whitespace()).collect_tuple() {
+            result.insert(source..source + len, dest);
         } else {
-            let next_len = next_boundary - start;
-            result.push(((start as isize + offset) as usize, next_len));
-            result.append(&mut self.apply_to_range((next_boundary, len - next_len)));
+            panic!("{} is malformed", line);
         }
-        result
-    }
-
-    fn apply_to_ranges<'a>(&'a self, ranges: impl Iterator<Item = (usize, usize)> + 'a) -> impl Iterator<Item = (usize, usize)> + 'a
-    {
-        ranges.map(|range| self.apply_to_range(range).into_iter()).flatten()
-    }
-
-    fn get_next_boundary(&self, input: usize) -> (Option<usize>, isize) {
-        if input < self.min_mapped {
-            // below the first range - next boundary is the first start, and the transformation is identity
-            return (Some(self.storage[0].1), 0)
-        }
-
-        for (i, (dest_start, source_start, len)) in self.storage.iter().enumerate() {
-            if *source_start <= input {
-                if input < source_start + len {
-                    // input is in one of our ranges - next boundary is the end of it
-                    return (Some(*source_start + len), *dest_start as isize - *source_start as isize);
-                }
-                if i < self.storage.len() - 1 && input < self.storage[i+1].1 {
-                    // input is outside any range - next boundary is start of the next, if it
-                    // exists, and we're doing no transformation
-                    return (Some(self.storage[i+1].1), 0);
-                }
-            }
-        }
-        (None, 0)
-    }
-
-    fn get(&self, index: usize) -> usize {
-        for (dest_start, source_start, len) in self.storage.iter() {
-            if source_start <= &index && index < source_start + len {
-                return dest_start + index - source_start;
-            }
-        }
-        index
     }
+    result
 }
 
 fn main() {
@@ -96,37 +34,18 @@ fn main() {
 
     let mut lines = lines.skip(1);
 
-    // let seeds_soil = RangeMap::from_lines(&mut lines.skip(1));
-    // let soil_fertilizer = RangeMap::from_lines(&mut lines.skip(1));
-    // let fertilizer_water = RangeMap::from_lines(&mut lines.skip(1));
-    // let water_light = RangeMap::from_lines(&mut lines.skip(1));
-    // let light_temp = RangeMap::from_lines(&mut lines.skip(1));
-    // let temp_humidity = RangeMap::from_lines(&mut lines.skip(1));
-    // let humidity_location = RangeMap::from_lines(&mut lines.skip(1));
+    // seed-to-soil, soil-to-fertilizer, ... humidity-to-location, all in one go
+    let seed_to_location = (0..7)
+        .map(|_| map_from_lines(&mut lines.by_ref().skip(1)))
+        .reduce(|acc, map| acc.compose(&map))
+        .unwrap();
 
-    let maps = (0..7)
-        .map(|_| RangeMap::from_lines(&mut lines.by_ref().skip(1)))
-        .collect::<Vec<_>>();
-
-    let locations = seeds.iter().map(|seed| {
-        let mut idx = *seed;
-        for map in maps.iter() {
-            idx = map.get(idx);
-        }
-        idx
-    });
-    println!("{}", locations.min().unwrap());
-    let seed_range_chunks = seeds
+    println!("{}", seeds.iter().map(|seed| seed_to_location.get(*seed)).min().unwrap());
+    println!("{}", seeds
         .iter()
-        .chunks(2);
-    let seed_ranges: Box<dyn Iterator<Item=_>> = Box::new(seed_range_chunks
-        .into_iter()
-        .map(|pair| pair.copied().collect_tuple::<(_, _)>().unwrap()));
-
-    let idx_ranges = maps.iter().fold(seed_ranges, |acc, map| Box::new(map.apply_to_ranges(acc)));
-    // let mut idx_ranges = seed_ranges;
-    // for map in maps.iter() {
-    //     idx_ranges = map.apply_to_ranges(idx_ranges);
-    // }
-    println!("{}", idx_ranges.map(|(start, _)| start).min().unwrap());
-}
\ No newline at end of file
+        .tuples()
+        .filter_map(|(start, len)| seed_to_location.min_image(*start..start + len))
+        .min()
+        .unwrap()
+    );
+}
@@ -1,10 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::ops::Rem;
 use bit_set::BitSet;
 use itertools::Itertools;
-use modinverse::egcd;
 use aoc2023::common::read_input_lines;
-use num::integer::lcm;
+use aoc2023::intern::Interner;
+use aoc2023::math::checked_lcm;
 
 fn instr(c: char) -> bool {
     match c {
@@ -44,24 +44,25 @@ fn main () {
 
     let nodes_lines = lines.skip(1).collect::<Vec<_>>();
     let nodes = nodes_lines.iter().map(nodeline);
-    let names_to_idx = HashMap::<&str, usize>::from_iter(nodes
-        .clone()
-        .enumerate()
-        .map(|(i, (from, _))| (from, i)));
+    let mut names = Interner::new();
+    for (from, _) in nodes.clone() {
+        names.intern(from);
+    }
+    let idx = |name| names.get(name).unwrap() as usize;
     let nodes = nodes
-        .map(|(_, (left, right))| (names_to_idx[left], names_to_idx[right]))
+        .map(|(_, (left, right))| (idx(left), idx(right)))
         .collect::<Vec<_>>();
 
-    let part1_start = names_to_idx["AAA"];
-    let targets = BitSet::<u16>::from_iter(names_to_idx.iter().filter_map(
-        |(name, idx)| match name.as_bytes()[2] as char {
-            'Z' => Some(*idx),
+    let part1_start = idx("AAA");
+    let targets = BitSet::<u16>::from_iter(names.iter().filter_map(
+        |(idx, name)| match name.as_bytes()[2] as char {
+            'Z' => Some(idx as usize),
             _ => None,
         }
     ));
-    let mut paths = names_to_idx.iter().filter_map(
-        |(name, idx)| match name.as_bytes()[2] as char {
-            'A' => Some(*idx),
+    let mut paths = names.iter().filter_map(
+        |(idx, name)| match name.as_bytes()[2] as char {
+            'A' => Some(idx as usize),
             _ => None,
         }
     );
@@ -70,6 +71,5 @@ fn main () {
         follow_path(&nodes, &targets, &instructions, p, p == part1_start)
     );
 
-    let lcm = so_called_cycle_lengths.clone().reduce(lcm).unwrap();
-    println!("{}", lcm);
+    println!("{}", checked_lcm(so_called_cycle_lengths).expect("lcm overflowed"));
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
-use std::collections::HashMap;
 use aoc2023::common::{read_input_lines, strs_to_nums};
+use aoc2023::memo::Memoized;
 
 pub struct Intersperse<I, T> {
     orig: I,
@@ -46,9 +46,8 @@ struct Nonogram1D {
     clues: Vec<usize>,
 }
 
-static mut HIT: usize = 0;
-static mut MISS: usize = 0;
-type Cache<'a> = HashMap<(String, Vec<usize>), usize>;
+// a subproblem is the board and clues from these offsets onwards
+type Suffix = (usize, usize);
 
 impl Nonogram1D {
     fn from_line(line: &String) -> Nonogram1D {
@@ -70,11 +69,11 @@ impl Nonogram1D {
         Nonogram1D{board, clues}
     }
 
-    fn solutions(&mut self, cache: &mut Cache) -> usize {
-        self.n_valid_boards(cache, self.board.clone(), self.clues.clone())
+    fn solutions(&self) -> usize {
+        Memoized::new(|rec, suffix| self.n_valid_boards(rec, suffix)).call(&(0, 0))
     }
 
-    fn maybe_valid(&self, board: &String, clues: &Vec<usize>, new_pos: usize, new_value: u8) -> (usize, usize, bool) {
+    fn maybe_valid(&self, board: &str, clues: &[usize], new_pos: usize, new_value: u8) -> (usize, usize, bool) {
         let mut block_len = 0;
         let mut clue_idx = 0;
         for (i, cell) in board.bytes().enumerate() {
@@ -124,22 +123,9 @@ impl Nonogram1D {
         (board.len(), clues.len(), clue_idx == clues.len() || clue_idx == clues.len() - 1 && block_len == clues[clue_idx])
     }
 
-    fn cached_n_valid_boards(&mut self, cache: &mut Cache, board: String, clues: Vec<usize>) -> usize {
-        if let Some(result) = cache.get(&(board.to_string(), clues.to_vec())) {
-            unsafe { HIT += 1; }
-            *result
-        } else {
-            let board_clone = board.clone().clone();
-            let clues_clone = clues.clone().clone();
-
-            let result = self.n_valid_boards(cache, board, clues);
-            cache.insert((board_clone, clues_clone), result);
-            unsafe { MISS += 1; }
-            result
-        }
-    }
-
-    fn n_valid_boards(&mut self, cache: &mut Cache, board: String, clues: Vec<usize>) -> usize {
+    fn n_valid_boards(&self, rec: &dyn Fn(&Suffix) -> usize, &(board_start, clues_start): &Suffix) -> usize {
+        let board = &self.board[board_start..];
+        let clues = &self.clues[clues_start..];
         if board.len() == 0 && clues.len() == 0 {
             return 1;
         }
@@ -149,23 +135,16 @@ impl Nonogram1D {
         for (i, b) in board.bytes().enumerate() {
             if b == b'?' {
                 found = true;
-                let (skip, clue_idx, valid) = self.maybe_valid(&board, &clues, i, b'#');
-                if valid {
-                    let new_board = board.chars().skip(skip).collect();
-                    let new_clues = clues[clue_idx..].to_vec();
-                    result += self.cached_n_valid_boards(cache, new_board, new_clues);
-                }
-
-                let (skip, clue_idx, valid) = self.maybe_valid(&board, &clues, i, b'.');
-                if valid {
-                    let new_board = board.chars().skip(skip).collect();
-                    let new_clues = clues[clue_idx..].to_vec();
-                    result += self.cached_n_valid_boards(cache, new_board, new_clues);
+                for new_value in [b'#', b'.'] {
+                    let (skip, clue_idx, valid) = self.maybe_valid(board, clues, i, new_value);
+                    if valid {
+                        result += rec(&(board_start + skip, clues_start + clue_idx));
+                    }
                 }
                 break;
             }
         }
-        if !found && self.maybe_valid(&board, &clues, 1_000_000, 0).2 {
+        if !found && self.maybe_valid(board, clues, 1_000_000, 0).2 {
             result + 1
         } else {
             result
@@ -176,8 +155,6 @@ impl Nonogram1D {
 fn main() {
     let lines = read_input_lines().unwrap().collect::<Vec<_>>();
 
-    let mut cache = Cache::new();
-
-    println!("{}", lines.iter().map(|l| Nonogram1D::from_line(&l).solutions(&mut cache)).sum::<usize>());
-    println!("{}", lines.iter().map(|l| Nonogram1D::expand_from_line(&l).solutions(&mut cache)).sum::<usize>());
-}
\ No newline at end of file
+    println!("{}", lines.iter().map(|l| Nonogram1D::from_line(l).solutions()).sum::<usize>());
+    println!("{}", lines.iter().map(|l| Nonogram1D::expand_from_line(l).solutions()).sum::<usize>());
+}
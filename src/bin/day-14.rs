@@ -1,11 +1,11 @@
-use std::collections::HashMap;
-use rustc_hash::FxHashMap;
 use std::fmt::{Debug, Display, Formatter};
 use bitvec::prelude::*;
 use itertools::{Either, Itertools};
 use aoc2023::common::read_input;
-use aoc2023::coord::Pt;
+use aoc2023::cycle;
+use aoc2023::coord::{Pt, Point};
 
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct PointSet {
     width: usize,
     height: usize,
@@ -20,11 +20,11 @@ impl PointSet {
     }
 
     fn set(&mut self, p: Pt<usize>, value: bool) {
-        self.storage.set(p.0 + p.1 * self.width, value);
+        self.storage.set(p.x() + p.y() * self.width, value);
     }
 
     fn contains(&self, p: Pt<usize>) -> bool {
-        self.storage[p.0 + p.1 * self.width]
+        self.storage[p.x() + p.y() * self.width]
     }
 
     fn pt(&self, i: usize) -> Pt<usize> {
@@ -70,29 +70,29 @@ fn shift(board: &mut PointSet, solid: &PointSet, by: Pt<isize>) {
     let bugger_off_rust = board.storage.clone();
     let ones = bugger_off_rust.iter_ones();
     let positions = match by {
-        Pt(1, 0) => Either::Left(ones.rev()),
-        Pt(-1, 0) => Either::Right(ones),
-        Pt(0, 1) => Either::Left(ones.rev()),
-        Pt(0, -1) => Either::Right(ones),
+        Point([1, 0]) => Either::Left(ones.rev()),
+        Point([-1, 0]) => Either::Right(ones),
+        Point([0, 1]) => Either::Left(ones.rev()),
+        Point([0, -1]) => Either::Right(ones),
         _ => panic!("can't handle shift direction {by}")
     };
 
     for p in positions {
         let mut p = board.pt(p);
-        let pp: Pt<isize> = p.into();
-        let mut next = pp + by;
-        while next.0 >= 0 &&
-            next.1 >= 0 &&
-            next.0 < board.width as isize &&
-            next.1 < board.height as isize &&
-            !solid.contains(next.into()) &&
-            !board.contains(next.into())
+        let mut next = p.signed() + by;
+        while next.x() >= 0 &&
+            next.y() >= 0 &&
+            next.x() < board.width as isize &&
+            next.y() < board.height as isize
         {
+            let q: Pt<usize> = next.try_into().unwrap();
+            if solid.contains(q) || board.contains(q) {
+                break;
+            }
             board.set(p, false);
-            board.set(next.into(), true);
-            p = next.into();
-            let pp: Pt<isize> = p.into();
-            next = pp + by;
+            board.set(q, true);
+            p = q;
+            next += by;
         }
     }
 }
@@ -109,7 +109,7 @@ const DIRECTIONS: [Pt<isize>; 4] = [
     Pt(1, 0),
 ];
 
-fn cycle(board: &mut PointSet, solid: &PointSet) {
+fn spin(board: &mut PointSet, solid: &PointSet) {
     for dir in DIRECTIONS {
         shift(board, solid, dir);
     }
@@ -136,29 +136,17 @@ fn main() {
         shift(&mut rollinghams, &walls, DIRECTIONS[0]);
         println!("{}", weight(&rollinghams.storage, height));
 
-        let mut seen_to_iter = FxHashMap::default();
-        let mut iter_to_seen = vec![];
         for dir in &DIRECTIONS[1..] {
             shift(&mut rollinghams, &walls, *dir);
         }
 
+        // we've done one full cycle already
         const TARGET: usize = 1_000_000_000;
-
-        for i in 1..10000 {
-            cycle(&mut rollinghams, &walls);
-            match seen_to_iter.get(&rollinghams.storage) {
-                Some(j) => {
-                    let remainder = (TARGET - j - 1) % (i-j);
-                    println!("{}", weight(&iter_to_seen[j + remainder - 1], height));
-                    return;
-                },
-                None => {
-                    seen_to_iter.insert(rollinghams.storage.clone(), i);
-                    iter_to_seen.push(rollinghams.storage.clone());
-                },
-            };
-        }
+        let spun = cycle::nth_state(rollinghams, |board| {
+            let mut board = board.clone();
+            spin(&mut board, &walls);
+            board
+        }, TARGET - 1);
+        println!("{}", weight(&spun.storage, height));
     }
-
-    panic!("Didn't find a cycle after 10000 iterations");
-}
\ No newline at end of file
+}
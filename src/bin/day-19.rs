@@ -1,7 +1,8 @@
-use std::cmp::{max, min};
-use std::ops::{BitAndAssign, Index, IndexMut, Not};
-use array_macro::array;
+use std::ops::Index;
 use aoc2023::common::read_input_lines;
+use aoc2023::coord::Point;
+use aoc2023::geometry::AABox;
+use aoc2023::intern::Interner;
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 enum Var { X, M, A, S }
@@ -11,16 +12,6 @@ enum Comp { LT, GT }
 
 type RuleIndex = usize;
 
-fn idx(s: &str) -> usize {
-    let s = s.as_bytes();
-    let mut result = 0;
-    for c in s.iter().take(3) {
-        result *= 26;
-        result += (*c - b'a') as usize;
-    }
-    result
-}
-
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Target {
     Workflow(RuleIndex),
@@ -29,11 +20,11 @@ enum Target {
 }
 
 impl Target {
-    fn from_str(s: &str) -> Self {
+    fn from_str(s: &str, labels: &mut Interner) -> Self {
         match s.as_bytes()[0] {
             b'A' => Target::Accept,
             b'R' => Target::Reject,
-            _ => Target::Workflow(idx(s)),
+            _ => Target::Workflow(labels.intern(s) as RuleIndex),
         }
     }
 }
@@ -47,7 +38,7 @@ struct Rule {
 }
 
 impl Rule {
-    fn from_str(rule_str: &str) -> Self {
+    fn from_str(rule_str: &str, labels: &mut Interner) -> Self {
         let (cond, target) = rule_str.split_once(':').unwrap();
         let var = match cond.as_bytes()[0] {
             b'x' => Var::X,
@@ -62,27 +53,11 @@ impl Rule {
             Comp::GT
         };
         let threshold = cond[2..].parse().expect("invalid u16");
-        let target = Target::from_str(target);
+        let target = Target::from_str(target, labels);
         Rule { var, comp, threshold, target }
     }
 }
 
-impl Not for &Rule {
-    type Output = Rule;
-
-    fn not(self) -> Self::Output {
-        // negate the rule
-        Rule {
-            var: self.var,
-            comp: if self.comp == Comp::LT { Comp::GT } else { Comp::LT },
-            threshold: if self.comp == Comp::LT { self.threshold - 1 } else { self.threshold + 1 },
-            // bleh... it doesn't make sense to "negate" the target, but where we need this, we only
-            // care about the other bits anyway.
-            target: Target::Accept
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 struct WorkFlow {
     rules: Vec<Rule>,
@@ -111,83 +86,44 @@ impl Index<Var> for Part {
     }
 }
 
-// open intervals (.0, .1) --> the range 1 to 4000 inclusive is represented by (0, 4001)
-// 1 for each of the four variables
-type Restrictions = [(u16, u16); 4];
-
-impl Index<Var> for Restrictions {
-    type Output = (u16, u16);
-
-    fn index(&self, index: Var) -> &Self::Output {
-        &self[index as usize]
-    }
-}
-
-impl IndexMut<Var> for Restrictions {
-    fn index_mut(&mut self, index: Var) -> &mut Self::Output {
-        &mut self[index as usize]
-    }
-}
-
-// why can we implement (arbitrary) traits for a type alias but not arbitrary methods?!
-trait Restrictiony {
-    fn valid(&self, rule: &Rule) -> bool;
-    fn intersection_inplace(&mut self, rule: &Rule);
-    fn intersection(&self, rule: &Rule) -> Restrictions;
-    fn volume(&self) -> usize;
-}
+// one axis for each of the four variables
+type Restrictions = AABox<usize, 4>;
 
-impl Restrictiony for Restrictions {
-    fn valid(&self, rule: &Rule) -> bool {
-        let (low, high) = self[rule.var];
-
-        if rule.comp == Comp::GT {
-            rule.threshold <= high
-        } else {
-            rule.threshold >= low
-        }
-    }
-
-    fn intersection_inplace(&mut self, rule: &Rule) {
-        if rule.comp == Comp::GT {
-            self[rule.var].0 = max(self[rule.var].0, rule.threshold);
-        } else {
-            self[rule.var].1 = min(self[rule.var].1, rule.threshold);
-        }
-    }
-
-    fn intersection(&self, rule: &Rule) -> Restrictions {
-        let mut result = self.clone();
-        if rule.comp == Comp::GT {
-            result[rule.var].0 = max(result[rule.var].0, rule.threshold);
-        } else {
-            result[rule.var].1 = min(result[rule.var].1, rule.threshold);
+impl Rule {
+    // the part of the restrictions which passes this rule, and the part which doesn't
+    fn split(&self, restrictions: &Restrictions) -> (Option<Restrictions>, Option<Restrictions>) {
+        let threshold = self.threshold as usize;
+        match self.comp {
+            Comp::LT => restrictions.split(self.var as usize, threshold),
+            Comp::GT => {
+                let (fail, pass) = restrictions.split(self.var as usize, threshold + 1);
+                (pass, fail)
+            }
         }
-        result
-    }
-
-    fn volume(&self) -> usize {
-        // subtract 1 from each length because the intervals are open
-        self.iter().map(|(low, high)| (high - low - 1) as usize).product()
     }
-
 }
 
-fn count_accepted(workflows: &[Option<WorkFlow>; 26 * 26 * 26], target: &Target, mut restrictions: Restrictions) -> usize {
+fn count_accepted(workflows: &[Option<WorkFlow>], target: &Target, restrictions: Restrictions) -> usize {
     // Recurse through the tree, keeping track of the restrictions we've acquired along the way.
     // If we hit "accept" return the remaining possibilities (the volume of the hypercube of restrictions).
     if let Target::Workflow(idx) = target {
         let workflow = workflows[*idx].as_ref().unwrap();
         let mut count = 0;
+        let mut remaining = Some(restrictions);
         for rule in workflow.rules.iter() {
-            if !restrictions.valid(&rule) {
-                continue;
+            let Some(restrictions) = remaining else {
+                break;
+            };
+            let (pass, fail) = rule.split(&restrictions);
+            if let Some(pass) = pass {
+                count += count_accepted(workflows, &rule.target, pass);
             }
-            count += count_accepted(workflows, &rule.target, restrictions.intersection(rule));
-            // If a criterion is not met, we must add in the negation of that criterion to continue
-            restrictions.intersection_inplace(&!rule);
+            // If a criterion is not met, we continue with the part that fails it
+            remaining = fail;
+        }
+        if let Some(restrictions) = remaining {
+            count += count_accepted(workflows, &workflow.default, restrictions);
         }
-        count += count_accepted(workflows, &workflow.default, restrictions);
         count
     } else if *target == Target::Accept {
         restrictions.volume()
@@ -197,8 +133,8 @@ fn count_accepted(workflows: &[Option<WorkFlow>; 26 * 26 * 26], target: &Target,
 }
 
 fn main() {
-    const NO_WORKFLOW: Option<WorkFlow> = None;
-    let mut workflows = [NO_WORKFLOW; 26 * 26 * 26];
+    let mut labels = Interner::new();
+    let mut workflows: Vec<Option<WorkFlow>> = vec![];
     let mut parts: Vec<Part> = vec![];
     let mut done_workflows = false;
 
@@ -214,12 +150,14 @@ fn main() {
             let mut rules = vec![];
             for rule_str in line.split(',') {
                 if rule_str.ends_with('}') {
-                    let default = Target::from_str(&rule_str[..rule_str.len() - 1]);
-                    workflows[idx(index)] = Some(WorkFlow { rules, default });
+                    let default = Target::from_str(&rule_str[..rule_str.len() - 1], &mut labels);
+                    let index = labels.intern(index) as RuleIndex;
+                    workflows.resize_with(labels.len(), || None);
+                    workflows[index] = Some(WorkFlow { rules, default });
                     break;
                 }
 
-                let rule = Rule::from_str(rule_str);
+                let rule = Rule::from_str(rule_str, &mut labels);
                 rules.push(rule);
             }
         } else {
@@ -237,7 +175,7 @@ fn main() {
     }
 
     // PART1
-    let start_index: RuleIndex = idx("in");
+    let start_index = labels.get("in").unwrap() as RuleIndex;
     let mut part1 = 0_usize;
 
     for part in parts.iter() {
@@ -256,7 +194,7 @@ fn main() {
     }
 
     // PART2
-    let part2 = count_accepted(&workflows, &Target::Workflow(start_index), array![(0, 4001); 4]);
+    let part2 = count_accepted(&workflows, &Target::Workflow(start_index), AABox::from_inclusive(Point([1; 4]), Point([4000; 4])));
     println!("{}", part1);
     println!("{}", part2);
 }
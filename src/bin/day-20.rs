@@ -1,20 +1,15 @@
 use std::any::{Any};
-use std::array;
 use std::collections::{HashMap, VecDeque};
 use std::fmt::{Debug};
-use aoc2023::common::read_input_lines;
+use aoc2023::common::{output_path, read_input_lines};
+use aoc2023::graph::{DotStyle, Graph};
+use aoc2023::intern::Interner;
+use aoc2023::math::lcm_wide;
 
 type Idx = usize;
 
-#[inline]
-fn idx(s: &str) -> Idx {
-    if s == "broadcaster" {
-        0
-    } else {
-        let s = s.as_bytes();
-        (s[0] - b'a' + 1) as usize * 27 + (s[1] - b'a' + 1) as usize
-    }
-}
+// interned before anything else, so it always gets the first id
+const BROADCASTER: Idx = 0;
 
 #[derive(Debug)]
 struct Module {
@@ -25,22 +20,24 @@ struct Module {
 
 impl Module {
     #[inline]
-    fn parse(s: &str, out_to_in: &mut [Vec<Idx>]) -> (Idx, Self) {
+    fn parse(s: &str, labels: &mut Interner, out_to_in: &mut Vec<Vec<Idx>>) -> (Idx, Self) {
         let (module, dests) = s.split_once(" -> ").unwrap();
         let (label, imp): (_, Box<dyn Moduley>) = match module.as_bytes()[0] {
             b'%' => (&module[1..], Box::new(FlipFlop{on: false})),
             b'&' => (&module[1..], Box::new(Nand{memory: vec![]})),
             _ => (module, Box::new(Broadcast{})),
         };
+        let label = labels.intern(label) as Idx;
         let dests = dests.split(", ").map(
             |s| {
-                let id = idx(s);
+                let id = labels.intern(s) as Idx;
+                out_to_in.resize_with(labels.len(), Vec::new);
                 let outs = &mut out_to_in[id];
-                outs.push(idx(label));
+                outs.push(label);
                 (id, outs.len() - 1)
             }
         ).collect();
-        (idx(label), Module{dests, imp})
+        (label, Module{dests, imp})
     }
 }
 
@@ -62,6 +59,8 @@ trait Moduley: Any + Debug {
     // for some reason you can't blanket implement this on the trait, and numerous other attempts
     // at getting the concrete type don't work
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    // the prefix it has in the input
+    fn symbol(&self) -> &'static str;
 }
 
 impl Moduley for Broadcast {
@@ -73,6 +72,9 @@ impl Moduley for Broadcast {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self as &mut dyn Any
     }
+    fn symbol(&self) -> &'static str {
+        ""
+    }
 }
 
 impl Moduley for FlipFlop {
@@ -90,6 +92,10 @@ impl Moduley for FlipFlop {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self as &mut dyn Any
     }
+
+    fn symbol(&self) -> &'static str {
+        "%"
+    }
 }
 
 impl Moduley for Nand {
@@ -103,11 +109,15 @@ impl Moduley for Nand {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self as &mut dyn Any
     }
+
+    fn symbol(&self) -> &'static str {
+        "&"
+    }
 }
 
 #[inline]
 fn part1(modules: &mut [Option<Module>], pulses: &mut VecDeque<(Idx, usize, bool)>, highs: &mut i32, lows: &mut i32) {
-    pulses.push_back((idx(&"broadcaster"), 0, false));
+    pulses.push_back((BROADCASTER, 0, false));
     while let Some((idx, input_idx, input)) = pulses.pop_front() {
         if input {
             *highs += 1;
@@ -120,7 +130,7 @@ fn part1(modules: &mut [Option<Module>], pulses: &mut VecDeque<(Idx, usize, bool
 
 #[inline]
 fn part2(modules: &mut [Option<Module>], pulses: &mut VecDeque<(Idx, usize, bool)>, i: usize, goals: &mut HashMap::<Idx, Option<usize>>) {
-    pulses.push_back((idx(&"broadcaster"), 0, false));
+    pulses.push_back((BROADCASTER, 0, false));
     while let Some((idx, input_idx, input)) = pulses.pop_front() {
         if let Some(output) = simulate(modules, pulses, idx, input_idx, input) {
             if output {
@@ -143,13 +153,36 @@ fn simulate(modules: &mut [Option<Module>], pulses: &mut VecDeque<(Idx, usize, b
     None
 }
 
+// the circuit as a graph, with each edge weighted by which input of its destination it feeds
+fn circuit(modules: &[Option<Module>], labels: &Interner) -> Graph<String, usize> {
+    let mut graph = Graph::new();
+    for (id, label) in labels.iter() {
+        let symbol = modules[id as usize].as_ref().map_or("", |module| module.imp.symbol());
+        graph.add_node(format!("{symbol}{label}"));
+    }
+    for (id, module) in modules.iter().enumerate() {
+        if let Some(module) = module {
+            for (dest, input_idx) in module.dests.iter() {
+                graph.add_edge(id, *dest, *input_idx);
+            }
+        }
+    }
+    graph
+}
+
 fn main() {
-    let mut modules: [Option<Module>; 27*27] = array::from_fn(|_| None);
-    let mut out_to_in: [_; 27*27] = array::from_fn(|_| Vec::<Idx>::new());
+    let mut labels = Interner::new();
+    labels.intern("broadcaster");
+    let mut modules: Vec<Option<Module>> = vec![];
+    let mut out_to_in: Vec<Vec<Idx>> = vec![];
     for line in read_input_lines().unwrap() {
-        let (idx, module) = Module::parse(&line, &mut out_to_in);
+        let (idx, module) = Module::parse(&line, &mut labels, &mut out_to_in);
+        modules.resize_with(labels.len(), || None);
         modules[idx] = Some(module);
     }
+    // some destinations (like rx) aren't modules, and the broadcaster has no inputs
+    modules.resize_with(labels.len(), || None);
+    out_to_in.resize_with(labels.len(), Vec::new);
 
     // set up the Nands' memories
     for (idx, module) in modules.iter_mut().enumerate() {
@@ -160,11 +193,15 @@ fn main() {
         }
     }
 
+    if let Some(path) = output_path() {
+        circuit(&modules, &labels).write_dot(path, DotStyle::Directed).unwrap();
+    }
+
     // find input to "rx"
-    let rx_input = &out_to_in[idx("rx")];
+    let rx_input = &out_to_in[labels.get("rx").unwrap() as Idx];
     debug_assert_eq!(rx_input.len(), 1);
     // find inputs to that. we assume these all go high periodically for one iteration and then go
-    // low again, and that the length of this period is prime.
+    // low again.
     let mut rx_input_inputs = HashMap::<Idx, Option<usize>>::from_iter(
         out_to_in[rx_input[0]].iter().map(|idx| (idx.clone(), None))
     );
@@ -184,8 +221,8 @@ fn main() {
         if rx_input_inputs.values().all(|v| v.is_some()) {
             // magic off by one who cares why at this point tbh
             // since we're assuming all these inputs are periodic, the desired final input occurs at
-            // their LCM. Since we're assuming the periods are primes, that's their product
-            println!("{}", rx_input_inputs.values().map(|v| v.unwrap() + 1).product::<usize>());
+            // their LCM
+            println!("{}", lcm_wide(rx_input_inputs.values().map(|v| v.unwrap() + 1)));
             break;
         }
         i += 1;
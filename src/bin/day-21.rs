@@ -1,169 +1,63 @@
-use num::{Integer, pow};
-use aoc2023::common::read_input_lines;
-use aoc2023::coord::{PointSet, Pt};
-
-fn spread(
-    map: &PointSet<usize>,
-    even: bool,
-    frontier: &mut PointSet<usize>,
-    reachable_odd: &mut PointSet<usize>,
-    reachable_even: &mut PointSet<usize>
-) {
-    let mut next_reachable = PointSet::new(map.width());
-
-    for p in frontier.iter() {
-        for neighbour in p.neighbours4() {
-            if p.0 < map.width() && p.1 < map.width() && !map.contains(neighbour) {
-                next_reachable.insert(neighbour)
-            }
-        }
+use aoc2023::common::read_input;
+use aoc2023::bitgrid::BitGrid;
+use aoc2023::coord::{Dir, Pt};
+use aoc2023::grid::Grid;
+use aoc2023::poly::{lagrange, Newton};
+
+// take one step from the frontier. reachable[p] holds everything reachable in a number of steps
+// with parity p, and the new frontier is added to reachable[parity]
+fn spread(map: &BitGrid, parity: usize, frontier: &mut BitGrid, reachable: &mut [BitGrid; 2]) {
+    let mut next_reachable = frontier.shifted(Dir::N);
+    for dir in [Dir::E, Dir::S, Dir::W] {
+        next_reachable |= &frontier.shifted(dir);
     }
 
-    next_reachable.storage.difference_with(&reachable_odd.storage);
-    next_reachable.storage.difference_with(&reachable_even.storage);
+    next_reachable.difference_with(map);
+    next_reachable.difference_with(&reachable[0]);
+    next_reachable.difference_with(&reachable[1]);
     *frontier = next_reachable;
-    if even {
-        reachable_even.storage.union_with(&frontier.storage);
-    } else {
-        reachable_odd.storage.union_with(&frontier.storage);
-    }
+    reachable[parity] |= frontier;
 }
 
-fn simulate_twice(map: &PointSet<usize>, initial: &mut PointSet<usize>, iters_a: usize, iters_b: usize) -> usize {
-    let mut even_reachable = PointSet::new(map.width());
-    let mut odd_reachable = PointSet::new(map.width());
-    for i in 0..iters_a {
-        spread(&map, i % 2 == 0, initial, &mut even_reachable, &mut odd_reachable);
-    }
-    let to_use = if iters_a % 2 == 0 {
-        &even_reachable
-    } else {
-        &odd_reachable
-    };
-    let count_a = to_use.storage.iter().count();
-
-    for i in 0..(iters_b - iters_a) {
-        spread(&map, (iters_a + i) % 2 == 0, initial, &mut even_reachable, &mut odd_reachable);
-    }
-
-    *initial = if iters_b % 2 == 0 {
-        even_reachable
-    } else {
-        odd_reachable
-    };
-
-    count_a
-}
-
-fn count_rect(large: &PointSet<usize>, left: usize, top: usize, size: usize) -> usize {
-    let mut acc = 0;
-    for y in top..top + size {
-        for x in left..left + size {
-            acc += large.contains(Pt(x, y)) as usize;
+// the number of plots reachable in exactly n steps, for each n in steps
+fn reachable_counts(map: &BitGrid, start: Pt<usize>, steps: &[usize]) -> Vec<usize> {
+    let mut frontier = BitGrid::new(map.width, map.height);
+    frontier.insert(start);
+    let mut reachable = [frontier.clone(), BitGrid::new(map.width, map.height)];
+    let mut counts = vec![0; steps.len()];
+    for i in 0..=*steps.iter().max().unwrap() {
+        for (count, _) in counts.iter_mut().zip(steps).filter(|(_, &n)| n == i) {
+            *count = reachable[i % 2].count_ones();
         }
+        spread(map, (i + 1) % 2, &mut frontier, &mut reachable);
     }
-    acc
+    counts
 }
 
 fn main() {
-    let input = read_input_lines().unwrap().collect::<Vec<_>>();
-    let tile_width = input[0].len();
-
-    let mut tiled_map = PointSet::new(tile_width * 5);
-    let mut start = Pt(0, 0);
-
-    for (y, line) in input.into_iter().enumerate() {
-        for (x, c) in line.as_bytes().into_iter().enumerate() {
-            if *c == b'#' {
-                for ty in 0..5 {
-                    for tx in 0..5 {
-                        tiled_map.insert(Pt(tx * tile_width + x, ty * tile_width + y));
-                    }
-                }
-            } else if *c == b'S' {
-                start = Pt(tile_width * 2 + x, tile_width * 2 + y);
-            }
-        }
-    }
-
-    const part2_target : usize = 26501365;
-    let part1_target = 64;
-    let (full_iters, remaining_iters) = part2_target.div_mod_floor(&tile_width);
-
-    let mut reachable = PointSet::new(tile_width * 5);
-    reachable.insert(start);
-    let part1 = simulate_twice(&tiled_map, &mut reachable, part1_target, tile_width * 2 + remaining_iters);
-    println!("{}", part1);
-
-
-    let full_even_per_tile = count_rect(&reachable, tile_width * 2, tile_width * 2, tile_width);
-    let full_odd_per_tile = count_rect(&reachable, tile_width * 2, tile_width * 1, tile_width);
-
-    // on parity = 0, we are filling corner tiles of the same parity as the origin
-    let tile_parity = full_iters % 2;
-    // call "even" tiles those aligned with the origin tile
-    let (full_even, full_odd) = if tile_parity == 0 {
-        (pow(full_iters - 1, 2), pow(full_iters, 2))
-    } else {
-        (pow(full_iters, 2), pow(full_iters - 1, 2))
-    };
-
-    // println!("tile parity: {tile_parity}. full even tiles: {full_even}, full odd tiles {full_odd}");
-    let full_odd_count = full_even * full_even_per_tile;
-    let full_even_count = full_odd * full_odd_per_tile;
-    // println!(
-    //     "fully covered after {} ({} edge-to-edges): {} tiles; {} area",
-    //     TARGET, full_iters, full_odd + full_even, full_even_count + full_odd_count
-    // );
-    // println!("remaining iterations: {}; approx tiles: {}",
-    //          remaining_iters, 4 * remaining_iters * full_tile_occupied);
-    // println!("approx total: {}", full_odd_count + 4 * remaining_iters * full_tile_occupied / 2);
-
-    // println!("Full tile: {full_even}*{full_even_per_tile}, odd: {full_odd}*{full_odd_per_tile}");
-
-    // we will have 4 * each of these (but each one of the four will be a different fill pattern)
-    let low_perimeters = if tile_parity == 0 {
-        full_iters - 1
-    } else {
-        full_iters
-    };
-    let high_perimeters = if tile_parity == 0 {
-        full_iters
-    } else {
-        full_iters - 1
-    };
-
-    let even_ul_per_tile = count_rect(&reachable, tile_width, tile_width, tile_width);
-    let odd_ul_per_tile = count_rect(&reachable, 0, tile_width, tile_width);
-
-    let even_ur_per_tile = count_rect(&reachable, 3 * tile_width, tile_width, tile_width);
-    let odd_ur_per_tile = count_rect(&reachable, 4 * tile_width, tile_width, tile_width);
-
-    let even_br_per_tile = count_rect(&reachable, 3 * tile_width, 3 * tile_width, tile_width);
-    let odd_br_per_tile = count_rect(&reachable, 4 * tile_width, 3 * tile_width, tile_width);
-
-    let even_bl_per_tile = count_rect(&reachable, tile_width, 3 * tile_width, tile_width);
-    let odd_bl_per_tile = count_rect(&reachable, 0, 3 * tile_width, tile_width);
-
-    let even_l = count_rect(&reachable, 0, 2 * tile_width, tile_width);
-    // let odd_l = count_rect(&reachable, 0, 2 * tile_width, tile_width);
-
-    let even_u = count_rect(&reachable, 2 * tile_width, 0, tile_width);
-    // let odd_l = count_rect(&reachable, 0, 2 * tile_width, tile_width);
-
-    let even_r = count_rect(&reachable, 4 * tile_width, 2 * tile_width, tile_width);
-    // let odd_l = count_rect(&reachable, 0, 2 * tile_width, tile_width);
-
-    let even_b = count_rect(&reachable, 2 * tile_width, 4 * tile_width, tile_width);
-    // let odd_l = count_rect(&reachable, 0, 2 * tile_width, tile_width);
-
-    println!(
-        "{}",
-        low_perimeters * (even_ur_per_tile + even_ul_per_tile + even_bl_per_tile + even_br_per_tile) +
-            high_perimeters * (odd_ul_per_tile + odd_ur_per_tile + odd_bl_per_tile + odd_br_per_tile) +
-            even_l + even_u + even_r + even_b +
-            full_odd_count + full_even_count
-    );
-
-    return;
-}
\ No newline at end of file
+    let tile: Grid<char> = Grid::parse(&read_input().unwrap()).unwrap();
+    let tile_width = tile.width;
+    let start = tile.enumerate().find(|(_, c)| **c == 'S').unwrap().0;
+
+    const PART2_TARGET: usize = 26501365;
+
+    // The start is in the middle of the tile and its row and column are clear, so every tile_width
+    // steps the diamond reaches one more tile in each direction and the count grows quadratically.
+    // Sample once per tile out to 3 tiles (plus one more sample to check it's really quadratic),
+    // which fits in a 7x7 tiling.
+    const TILES: usize = 7;
+    let tiled_map = BitGrid::from(&tile.map(|c| *c == '#').tile(TILES, TILES));
+    let start = Pt(tile_width * (TILES / 2) + start.x(), tile_width * (TILES / 2) + start.y());
+    let steps = (0..=TILES / 2).map(|k| PART2_TARGET % tile_width + k * tile_width).collect::<Vec<_>>();
+    let counts = reachable_counts(&tiled_map, start, &[&[64], steps.as_slice()].concat());
+    println!("{}", counts[0]);
+
+    // the samples are evenly spaced, so their differences show whether it really is quadratic
+    let counts = counts[1..].iter().map(|&c| c as i128).collect::<Vec<_>>();
+    Newton::fit(&counts, 2).expect("reachable plots don't grow quadratically");
+    // then interpolate through the actual step counts, so the target needs no rescaling
+    let points = steps.iter().zip(counts).take(3).map(|(&n, c)| (n as i128, c)).collect::<Vec<_>>();
+    let part2 = lagrange(&points, PART2_TARGET as i128).unwrap();
+    assert!(part2.is_integer());
+    println!("{}", part2.to_integer());
+}
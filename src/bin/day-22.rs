@@ -1,31 +1,20 @@
-use std::cmp::{max, min};
+use std::cmp::max;
 use std::collections::VecDeque;
 use std::io::BufRead;
 use bit_set::BitSet;
 use itertools::{iproduct, Itertools};
 use aoc2023::common::{read_input_lines, strs_to_nums};
+use aoc2023::coord::{Pt as Pt2, Pt3};
+use aoc2023::geometry::AABox;
+use aoc2023::grid::Grid;
+use aoc2023::grid3::Grid3;
 
-#[derive(Debug, Clone)]
-struct Pt (usize, usize, usize);
-#[derive(Debug, Clone)]
-struct Brick (Pt, Pt);
+type Pt = Pt3<usize>;
+type Brick = AABox<usize, 3>;
 
-fn coord(p: &Pt) -> usize {
-    p.0 * 10 * 400 + p.1 * 400 + p.2
-}
-
-#[inline]
-fn dumbpt(p: (usize, usize, usize)) -> Pt {
-    Pt(p.0, p.1, p.2)
-}
-
-fn brick_pts(brick: &Brick) -> impl Iterator<Item=Pt> {
-    let Brick(start, end) = brick;
-    iproduct!(
-        min(start.0, end.0)..=max(start.0, end.0),
-        min(start.1, end.1)..=max(start.1, end.1),
-        min(start.2, end.2)..=max(start.2, end.2)
-    ).map(|(x, y, z)| Pt(x, y, z))
+// the columns a brick occupies
+fn footprint(brick: &Brick) -> impl Iterator<Item=Pt2<usize>> {
+    iproduct!(brick.min.x()..brick.max.x(), brick.min.y()..brick.max.y()).map(|(x, y)| Pt2(x, y))
 }
 
 fn chr(v: usize) -> char {
@@ -39,35 +28,46 @@ fn chr(v: usize) -> char {
     }
 }
 
-fn print_stack(stack: &Vec<usize>, height: usize) {
-    for z in (1..height+1).rev() {
-        for y in 0..10 {
-            for x in 0..10 {
-                print!("{}", chr(stack[coord(&Pt(x, y, z))]))
+// which brick (numbered from 1, 0 for empty) fills each cell. x, y, z are width, height, depth as
+// for Grid3, so "depth" here is how tall the stack is.
+fn occupancy(bricks: &[Brick], width: usize, height: usize, depth: usize) -> Grid3<usize> {
+    let mut stack = Grid3::new(width, height, depth);
+    for (brick_idx, brick) in bricks.iter().enumerate() {
+        for p in footprint(brick) {
+            for z in brick.min.z()..brick.max.z() {
+                stack[Pt3(p.x(), p.y(), z)] = brick_idx + 1;
             }
-            print!("|");
+        }
+    }
+    stack
+}
+
+fn print_stack(stack: &Grid3<usize>) {
+    for z in (1..stack.depth).rev() {
+        for row in stack.slice_z(z).rows() {
+            print!("{}|", row.iter().map(|v| chr(*v)).collect::<String>());
         }
         print!("\n");
     }
     print!("\n");
 }
 
-fn drop(bricks: &mut Vec<Brick>, height: usize) -> bool {
+fn drop(bricks: &mut Vec<Brick>, width: usize, height: usize) -> bool {
     let mut fallen = false;
 
-    let mut below = [0; 10*10];
+    let mut below: Grid<usize> = Grid::new(width, height);
     for brick in bricks.iter_mut() {
-        let dist = brick_pts(brick).map(|Pt(x, y, z)| z - below[x + y * 10]).min().unwrap();
+        let dist = footprint(brick).map(|p| brick.min.z() - below[p]).min().unwrap();
 
         if dist > 1 {
-            brick.0.2 -= dist - 1;
-            brick.1.2 -= dist - 1;
+            brick.min[2] -= dist - 1;
+            brick.max[2] -= dist - 1;
             fallen = true;
         }
 
-        brick_pts(brick).for_each(
-            |Pt(x, y, z)|
-                below[x + y * 10] = max(below[x + y * 10], z)
+        footprint(brick).for_each(
+            |p|
+                below[p] = max(below[p], brick.max.z() - 1)
         );
     }
 
@@ -108,37 +108,33 @@ fn main() {
     let input = read_input_lines().unwrap();
     let mut bricks = Vec::<Brick>::with_capacity(1400);
 
+    // x, y, z extents
+    let mut width = 0;
     let mut height = 0;
+    let mut depth = 0;
 
     for line in input {
         let (start, end) = line.split_once('~').unwrap();
-        let start = dumbpt(strs_to_nums(start.split(',')).collect_tuple().unwrap());
-        let end = dumbpt(strs_to_nums(end.split(',')).collect_tuple().unwrap());
+        let start: Pt = strs_to_nums(start.split(',')).collect_tuple::<(_, _, _)>().unwrap().into();
+        let end: Pt = strs_to_nums(end.split(',')).collect_tuple::<(_, _, _)>().unwrap().into();
 
-        height = max(height, max(start.2, end.2));
-        let brick = Brick(Pt(min(start.0, end.0), min(start.1, end.1), min(start.2, end.2)),
-                          Pt(max(start.0, end.0), max(start.1, end.1), max(start.2, end.2)));
+        let brick = Brick::from_inclusive(start, end);
+        width = max(width, brick.max.x());
+        height = max(height, brick.max.y());
+        depth = max(depth, brick.max.z());
 
         bricks.push(brick);
 
     }
 
     // STEP 1: drop blocks
-    bricks.sort_unstable_by_key(|brick| brick.0.2);
-    while drop(&mut bricks, height) {}
+    bricks.sort_unstable_by_key(|brick| brick.min.z());
+    while drop(&mut bricks, width, height) {}
 
     // relabel bricks in final height order
-    bricks.sort_unstable_by_key(
-        |Brick(Pt(_, _, z1), _)| *z1
-    );
-
-    let mut stack = vec![0; 10 * 10 * 400];
-    for (brick_idx, brick) in bricks.iter().enumerate() {
-        for p in brick_pts(&brick) {
-            stack[coord(&p)] = brick_idx + 1;
-        }
-    }
-    // print_stack(&stack, height);
+    bricks.sort_unstable_by_key(|brick| brick.min.z());
+    let stack = occupancy(&bricks, width, height, depth);
+    // print_stack(&stack);
 
     // STEP 2: build digraph of supports
 
@@ -147,17 +143,13 @@ fn main() {
     // brick --> bricks it's supported by
     let mut supported_by = vec![BitSet::new(); bricks.len() + 1];
 
+    // a brick rests on whatever is in the cells just under its footprint
     for (brick_idx, brick) in bricks.iter().enumerate() {
-        for Pt(x, y, z) in brick_pts(brick) {
-            let below_p = Pt(x, y, z - 1);
-            // are we on the floor, or is the space below not-empty and not-me?
-            if below_p.2 > 0 &&
-                stack[coord(&below_p)] != 0 &&
-                stack[coord(&below_p)] != brick_idx + 1
-            {
-                let below_brick = stack[coord(&below_p)];
-                supports[below_brick].insert(brick_idx + 1);
-                supported_by[brick_idx + 1].insert(below_brick);
+        for p in footprint(brick) {
+            let below = stack[Pt3(p.x(), p.y(), brick.min.z() - 1)];
+            if below != 0 {
+                supports[below].insert(brick_idx + 1);
+                supported_by[brick_idx + 1].insert(below);
             }
         }
     }
@@ -1,45 +1,56 @@
 use std::cmp::max;
 use bit_set::BitSet;
-use itertools::Itertools;
-use aoc2023::common::read_input_lines;
+use aoc2023::common::{output_path, read_input};
 use aoc2023::coord::{Pt, Dir, PointSet};
-use aoc2023::grid::Grid;
+use aoc2023::graph::{DotStyle, Graph};
+use aoc2023::grid::{Grid, GridChar};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum Tile {
     Wall, Floor, Ice(Dir)
 }
 
-impl Tile {
-    fn parse(c: u8) -> Self {
+impl GridChar for Tile {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            b'#' => Tile::Wall,
-            b'.' => Tile::Floor,
-            b'^' => Tile::Ice(Dir::N),
-            b'>' => Tile::Ice(Dir::E),
-            b'v' => Tile::Ice(Dir::S),
-            b'<' => Tile::Ice(Dir::W),
-            _ => Tile::Floor,
+            '#' => Some(Tile::Wall),
+            '.' => Some(Tile::Floor),
+            '^' => Some(Tile::Ice(Dir::N)),
+            '>' => Some(Tile::Ice(Dir::E)),
+            'v' => Some(Tile::Ice(Dir::S)),
+            '<' => Some(Tile::Ice(Dir::W)),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Wall => '#',
+            Tile::Floor => '.',
+            Tile::Ice(Dir::N) => '^',
+            Tile::Ice(Dir::E) => '>',
+            Tile::Ice(Dir::S) => 'v',
+            Tile::Ice(Dir::W) => '<',
         }
     }
 }
 
 fn part1(map: &Grid<Tile>, pos: Pt<isize>, target: Pt<isize>, mut hist: PointSet<usize>, dist: usize) -> usize {
-    if hist.contains(pos.into()) {
+    if hist.contains(pos.try_into().unwrap()) {
         return 0
     }
-    hist.insert(pos.into());
+    hist.insert(pos.try_into().unwrap());
     if pos == target {
         return dist;
     }
 
-    [Dir::N, Dir::E, Dir::S, Dir::W].iter().filter_map(|dir| {
+    Dir::ALL.iter().filter_map(|dir| {
         let next = pos.walk(*dir, 1);
         if !map.contains_isize(next) {
             return None;
         }
 
-        match map[next.into()] {
+        match map[next] {
             Tile::Wall => { None }
             Tile::Floor => { Some(part1(&map, next, target, hist.clone(), dist+1)) }
             Tile::Ice(next_dir) => {
@@ -53,26 +64,21 @@ fn part1(map: &Grid<Tile>, pos: Pt<isize>, target: Pt<isize>, mut hist: PointSet
     }).max().unwrap_or(0)
 }
 
-#[derive(Clone, Debug)]
-struct Edge {
-    weight: usize,
-    to: usize,
-}
-
-type Graph = Vec<Vec<Edge>>;
+// nodes are the junctions (plus the start and end), edges the corridors between them
+type Maze = Graph<Pt<usize>, usize>;
 
 fn contract(
     map: &Grid<Tile>,
-    pos: Pt<isize>,
+    pos: Pt<usize>,
     from: usize,
     dist: usize,
     forward: Option<bool>,
-    graph: &mut Graph,
-    digraph: &mut Graph,
+    graph: &mut Maze,
+    digraph: &mut Maze,
     // pt --> (node index, dist from node)
     map_to_graph: &mut Grid<Option<(usize, usize)>>,
 ) {
-    if let Some((node, d_dist)) = map_to_graph[pos.into()] {
+    if let Some((node, d_dist)) = map_to_graph[pos] {
         // println!("arrived at {pos} from {from} and it belongs to {node}");
         if node == from {
             // println!("    cycle");
@@ -81,34 +87,25 @@ fn contract(
         update_connection(from, d_dist + dist+1, forward.unwrap(), graph, digraph, node);
         return;
     } else {
-        map_to_graph[pos.into()] = Some((from, dist));
+        map_to_graph[pos] = Some((from, dist));
     }
-    let neighbours = [Dir::N, Dir::E, Dir::S, Dir::W].iter().map(|dir|
-        (dir, pos.walk(*dir, 1))).collect_vec();
-    // let neighbours = pos
-    //     .neighbours4();
-    let neighbours = neighbours
-        .iter()
-        .filter(
-            |(dir, p)|
-                map.contains_isize(*p) &&
-                map[(*p).into()] != Tile::Wall
-        )
+    let neighbours = map
+        .dir_neighbours4(pos)
+        .filter_map(|(dir, p, tile)| (*tile != Tile::Wall).then_some((dir, p)))
         .collect::<Vec<_>>();
 
     if neighbours.len() == 1 {
         // the only dead ends are the start and end and we make sure we don't go back to the start
         // so this is the end
-        let node = graph.len();
-        graph[from].push(Edge{weight: dist+1, to: node});
-        graph.push(vec![Edge{weight: dist+1, to: from }]);
-        digraph[from].push(Edge{weight: dist+1, to: node});
-        digraph.push(vec![]);
+        let node = graph.add_node(pos);
+        graph.add_undirected_edge(from, node, dist+1);
+        digraph.add_node(pos);
+        digraph.add_edge(from, node, dist+1);
     } else if neighbours.len() == 2 {
         // part of previous corridor
 
         for (dir, neighbour) in neighbours.iter() {
-            let next_node = map_to_graph[(*neighbour).into()];
+            let next_node = map_to_graph[*neighbour];
             if next_node.is_none() {
                 contract(map, *neighbour, from, dist + 1, forward, graph, digraph, map_to_graph);
             } else {
@@ -118,73 +115,58 @@ fn contract(
     } else {
         // junction
 
-        let node = graph.len();
-        graph.push(vec![Edge{weight: dist+1, to: from }]);
-        graph[from].push(Edge{weight: dist+1, to: node});
+        let node = graph.add_node(pos);
+        graph.add_undirected_edge(from, node, dist+1);
 
         let forward = forward.expect("found junction without determining a direction");
+        digraph.add_node(pos);
         if forward {
-            digraph.push(vec![]);
-            digraph[from].push(Edge{weight: dist+1, to: node});
+            digraph.add_edge(from, node, dist+1);
         } else {
-            digraph.push(vec![Edge{weight: dist+1, to: from }]);
-            // digraph[from].push(Edge{weight: dist+1, to: node});
+            digraph.add_edge(node, from, dist+1);
         }
 
         for (dir, neighbour) in neighbours.iter() {
-            if let Tile::Ice(tile_dir) = map[(*neighbour).into()] {
-                let forward = tile_dir == **dir;
+            if let Tile::Ice(tile_dir) = map[*neighbour] {
+                let forward = tile_dir == *dir;
                 // println!("{} {}", neighbour, forward);
                 contract(map, *neighbour, node, 0, Some(forward), graph, digraph, map_to_graph)
             } else {
-                panic!("bad direction at {neighbour}: {:?}", map[(*neighbour).into()]);
+                panic!("bad direction at {neighbour}: {:?}", map[*neighbour]);
             }
         }
     }
 }
 
-fn update_connection(from: usize, dist: usize, forward: bool, graph: &mut Graph, digraph: &mut Graph, next_node: usize) {
-    if let Some(existing_edge) = graph[next_node]
-        .iter_mut()
-        .find(|edge| edge.to == from)
-    {
-        // println!("    updating existing weight {} to {}", existing_edge.weight, dist + 1);
-        existing_edge.weight = max(existing_edge.weight, dist + 1);
-        graph[from]
-            .iter_mut()
-            .find(|edge| edge.to == next_node)
-            .unwrap()
-            .weight = max(existing_edge.weight, dist + 1);
+fn update_connection(from: usize, dist: usize, forward: bool, graph: &mut Maze, digraph: &mut Maze, next_node: usize) {
+    if let Some(existing_weight) = graph.edge_mut(next_node, from) {
+        // println!("    updating existing weight {} to {}", existing_weight, dist + 1);
+        *existing_weight = max(*existing_weight, dist + 1);
+        let weight = *existing_weight;
+        *graph.edge_mut(from, next_node).unwrap() = weight;
     } else {
         // println!("    adding edge with weight {}", dist + 1);
         // although we have visited this position already, we haven't drawn a connection to
         // the "from" node
-        graph[next_node].push(Edge { weight: dist + 1, to: from });
-        graph[from].push(Edge { weight: dist + 1, to: next_node });
+        graph.add_undirected_edge(next_node, from, dist + 1);
     }
 
     if forward {
-        if digraph[from]
-            .iter_mut()
-            .find(|edge| edge.to == next_node).is_none()
-        {
+        if digraph.edge(from, next_node).is_none() {
             // although we have visited this position already, we haven't drawn a connection to
             // the "from" node
             // println!("    adding edge {}--{} with weight {}", from, next_node, dist + 1);
-            digraph[from].push(Edge { weight: dist + 1, to: next_node });
+            digraph.add_edge(from, next_node, dist + 1);
         }
     } else {
-        if digraph[next_node]
-            .iter_mut()
-            .find(|edge| edge.to == from).is_none()
-        {
+        if digraph.edge(next_node, from).is_none() {
             // println!("    adding edge {}--{} with weight {}", next_node, from, dist + 1);
-            digraph[next_node].push(Edge { weight: dist + 1, to: from });
+            digraph.add_edge(next_node, from, dist + 1);
         }
     }
 }
 
-fn longest_path(graph: &Graph, cur: usize, target: usize, mut hist: BitSet, dist: usize) -> usize {
+fn longest_path(graph: &Maze, cur: usize, target: usize, mut hist: BitSet, dist: usize) -> usize {
     if hist.contains(cur) {
         return 0
     }
@@ -193,17 +175,14 @@ fn longest_path(graph: &Graph, cur: usize, target: usize, mut hist: BitSet, dist
         return dist;
     }
 
-    graph[cur].iter().map(|edge| {
+    graph.edges(cur).map(|edge| {
         longest_path(graph, edge.to, target, hist.clone(), dist + edge.weight)
     }).max().unwrap_or(0)
 }
 
 
 fn main() {
-    let input = read_input_lines().unwrap();
-    let map = Grid::map_from_lines(
-        input.map(|line| line.as_bytes().to_owned()), Tile::parse
-    );
+    let map: Grid<Tile> = Grid::parse(&read_input().unwrap()).unwrap();
 
     let start = Pt(1_usize, 0);
     // let target = Pt(map.width - 2, map.height - 1);
@@ -211,14 +190,17 @@ fn main() {
 
     // println!("{}", part1(&map, start, target.into(), hist, 0));
 
-    let mut graph = vec![];
-    graph.push(vec![]);
-    let mut digraph = vec![];
-    digraph.push(vec![]);
+    let mut graph = Maze::new();
+    graph.add_node(start);
+    let mut digraph = Maze::new();
+    digraph.add_node(start);
     let mut map_to_graph = Grid::new(map.width, map.height);
-    map_to_graph[start.into()] = Some((0, 0));
+    map_to_graph[start] = Some((0, 0));
     contract(&map, Pt(1, 1), 0, 1, Some(true), &mut graph, &mut digraph, &mut map_to_graph);
-    let target = digraph.iter().find_position(|edges| edges.len() == 0).unwrap().0;
+    let target = (0..digraph.node_count()).find(|&n| digraph.out_degree(n) == 0).unwrap();
+    if let Some(path) = output_path() {
+        graph.write_dot(path, DotStyle::Undirected).unwrap();
+    }
     let hist = BitSet::new();
     // println!("{:#?}", graph);
     // println!("{:#?}", digraph);
@@ -1,38 +1,40 @@
 use itertools::Itertools;
-use num::BigInt;
+use num::{BigInt, BigRational};
 use num::integer::sqrt;
 use aoc2023::common::{read_input_lines, strs_to_nums};
+use aoc2023::coord::{Point, Pt3};
+use aoc2023::linalg::Matrix;
 
 #[derive(Debug, Clone)]
 struct Stone {
-    p: (isize, isize, isize),
-    v: (isize, isize, isize),
+    p: Pt3<isize>,
+    v: Pt3<isize>,
 }
 
 #[derive(Debug, Clone)]
 struct BigStone {
-    p: (BigInt, BigInt, BigInt),
-    v: (BigInt, BigInt, BigInt),
+    p: Pt3<BigInt>,
+    v: Pt3<BigInt>,
 }
 
 impl Stone {
     fn parse(s: String) -> Self {
         let (p, v) = s.split_once(" @ ").unwrap();
-        let p = strs_to_nums(p.split(',').map(|s| s.trim())).collect_tuple().unwrap();
-        let v = strs_to_nums(v.split(',').map(|s| s.trim())).collect_tuple().unwrap();
-        Stone{p, v}
+        let p = strs_to_nums(p.split(',').map(|s| s.trim())).collect_tuple::<(_, _, _)>().unwrap();
+        let v = strs_to_nums(v.split(',').map(|s| s.trim())).collect_tuple::<(_, _, _)>().unwrap();
+        Stone{p: p.into(), v: v.into()}
     }
 
     fn test(&self, other: &Self, area: (isize, isize)) -> bool {
-        if self.v.0 == 0 || self.v.1 * other.v.0 == self.v.0 * other.v.1 {
+        if self.v.x() == 0 || self.v.y() * other.v.x() == self.v.x() * other.v.y() {
             return false;
         }
 
-        let tb = (self.v.0 * (other.p.1 - self.p.1) - self.v.1 * (other.p.0 - self.p.0)) /
-            (self.v.1 * other.v.0 - self.v.0 * other.v.1);
-        let ta = (other.p.0 + tb * other.v.0 - self.p.0) / self.v.0;
-        let xa = self.p.0 + ta * self.v.0;
-        let ya = self.p.1 + ta * self.v.1;
+        let tb = (self.v.x() * (other.p.y() - self.p.y()) - self.v.y() * (other.p.x() - self.p.x())) /
+            (self.v.y() * other.v.x() - self.v.x() * other.v.y());
+        let ta = (other.p.x() + tb * other.v.x() - self.p.x()) / self.v.x();
+        let xa = self.p.x() + ta * self.v.x();
+        let ya = self.p.y() + ta * self.v.y();
 
         if ta >= 0 && tb >= 0 && xa >= area.0 && xa <= area.1 && ya >= area.0 && ya <= area.1 {
             return true;
@@ -41,108 +43,50 @@ impl Stone {
     }
 
     fn big(&self) -> BigStone {
-        BigStone{p: (self.p.0.into(), self.p.1.into(), self.p.2.into()), v: (self.v.0.into(), self.v.1.into(), self.v.2.into())}
+        BigStone{p: self.p.map(BigInt::from), v: self.v.map(BigInt::from)}
     }
 }
 
-#[inline]
-fn pos_at(s: &BigStone, t: &BigInt) -> (BigInt, BigInt, BigInt) {
-    (s.p.0.clone() + s.v.0.clone() * t.clone(), s.p.1.clone() + s.v.1.clone() * t.clone(), s.p.2.clone() + s.v.2.clone() * t.clone())
+// cross product as a matrix, so that cross_matrix(a) * b = a x b
+fn cross_matrix(a: &Pt3<BigInt>) -> [[BigInt; 3]; 3] {
+    let z = BigInt::from(0);
+    let Point([x, y, w]) = a.clone();
+    [
+        [z.clone(), -&w, y.clone()],
+        [w, z.clone(), -&x],
+        [-y, x, z],
+    ]
 }
 
-fn part2(stones: &[Stone]) -> isize {
-    let (a, b, c) = (&stones[0].big(), &stones[1].big(), &stones[2].big());
-
-    // let tc = (-a.v.0.clone()*a.p.1.clone()*b.p.2.clone() + a.v.0.clone()*a.p.1.clone()*c.p.2.clone() + a.v.0.clone()*a.p.2.clone()*b.p.1.clone() - a.v.0.clone()*a.p.2.clone()*c.p.1.clone() - a.v.0.clone()*b.p.1.clone()*c.p.2.clone() + a.v.0.clone()*b.p.2.clone()*c.p.1.clone() + a.v.1.clone()*a.p.0.clone()*b.p.2.clone() - a.v.1.clone()*a.p.0.clone()*c.p.2.clone() - a.v.1.clone()*a.p.2.clone()*b.p.0.clone() + a.v.1.clone()*a.p.2.clone()*c.p.0.clone() + a.v.1.clone()*b.p.0.clone()*c.p.2.clone() - a.v.1.clone()*b.p.2.clone()*c.p.0.clone() - a.v.2.clone()*a.p.0.clone()*b.p.1.clone() + a.v.2.clone()*a.p.0.clone()*c.p.1.clone() + a.v.2.clone()*a.p.1.clone()*b.p.0.clone() - a.v.2.clone()*a.p.1.clone()*c.p.0.clone() - a.v.2.clone()*b.p.0.clone()*c.p.1.clone() + a.v.2.clone()*b.p.1.clone()*c.p.0.clone() - a.p.0.clone()*b.v.1.clone()*b.p.2.clone() + a.p.0.clone()*b.v.1.clone()*c.p.2.clone() + a.p.0.clone()*b.v.2.clone()*b.p.1.clone() - a.p.0.clone()*b.v.2.clone()*c.p.1.clone() + a.p.1.clone()*b.v.0.clone()*b.p.2.clone() - a.p.1.clone()*b.v.0.clone()*c.p.2.clone() - a.p.1.clone()*b.v.2.clone()*b.p.0.clone() + a.p.1.clone()*b.v.2.clone()*c.p.0.clone() - a.p.2.clone()*b.v.0.clone()*b.p.1.clone() + a.p.2.clone()*b.v.0.clone()*c.p.1.clone() + a.p.2.clone()*b.v.1.clone()*b.p.0.clone() - a.p.2.clone()*b.v.1.clone()*c.p.0.clone() + b.v.0.clone()*b.p.1.clone()*c.p.2.clone() - b.v.0.clone()*b.p.2.clone()*c.p.1.clone() - b.v.1.clone()*b.p.0.clone()*c.p.2.clone() + b.v.1.clone()*b.p.2.clone()*c.p.0.clone() + b.v.2.clone()*b.p.0.clone()*c.p.1.clone() - b.v.2.clone()*b.p.1.clone()*c.p.0.clone()) /
-    //     (a.v.0.clone()*a.p.1.clone()*b.v.2.clone() - a.v.0.clone()*a.p.1.clone()*c.v.2.clone() - a.v.0.clone()*a.p.2.clone()*b.v.1.clone() + a.v.0.clone()*a.p.2.clone()*c.v.1.clone() + a.v.0.clone()*b.v.1.clone()*b.p.2.clone() - a.v.0.clone()*b.v.2.clone()*b.p.1.clone() + a.v.0.clone()*b.p.1.clone()*c.v.2.clone() - a.v.0.clone()*b.p.2.clone()*c.v.1.clone() - a.v.1.clone()*a.p.0.clone()*b.v.2.clone() + a.v.1.clone()*a.p.0.clone()*c.v.2.clone() + a.v.1.clone()*a.p.2.clone()*b.v.0.clone() - a.v.1.clone()*a.p.2.clone()*c.v.0.clone() - a.v.1.clone()*b.v.0.clone()*b.p.2.clone() + a.v.1.clone()*b.v.2.clone()*b.p.0.clone() - a.v.1.clone()*b.p.0.clone()*c.v.2.clone() + a.v.1.clone()*b.p.2.clone()*c.v.0.clone() + a.v.2.clone()*a.p.0.clone()*b.v.1.clone() - a.v.2.clone()*a.p.0.clone()*c.v.1.clone() - a.v.2.clone()*a.p.1.clone()*b.v.0.clone() + a.v.2.clone()*a.p.1.clone()*c.v.0.clone() + a.v.2.clone()*b.v.0.clone()*b.p.1.clone() - a.v.2.clone()*b.v.1.clone()*b.p.0.clone() + a.v.2.clone()*b.p.0.clone()*c.v.1.clone() - a.v.2.clone()*b.p.1.clone()*c.v.0.clone() - a.p.0.clone()*b.v.1.clone()*c.v.2.clone() + a.p.0.clone()*b.v.2.clone()*c.v.1.clone() + a.p.1.clone()*b.v.0.clone()*c.v.2.clone() - a.p.1.clone()*b.v.2.clone()*c.v.0.clone() - a.p.2.clone()*b.v.0.clone()*c.v.1.clone() + a.p.2.clone()*b.v.1.clone()*c.v.0.clone() - b.v.0.clone()*b.p.1.clone()*c.v.2.clone() + b.v.0.clone()*b.p.2.clone()*c.v.1.clone() + b.v.1.clone()*b.p.0.clone()*c.v.2.clone() - b.v.1.clone()*b.p.2.clone()*c.v.0.clone() - b.v.2.clone()*b.p.0.clone()*c.v.1.clone() + b.v.2.clone()*b.p.1.clone()*c.v.0.clone());
-
-    let mut tc_p = BigInt::from(0);
-    tc_p -= &a.v.0 * &a.p.1 * &b.p.2;
-    tc_p += &a.v.0 * &a.p.1 * &c.p.2;
-    tc_p += &a.v.0 * &a.p.2 * &b.p.1;
-    tc_p -= &a.v.0 * &a.p.2 * &c.p.1;
-    tc_p -= &a.v.0 * &b.p.1 * &c.p.2;
-    tc_p += &a.v.0 * &b.p.2 * &c.p.1;
-    tc_p += &a.v.1 * &a.p.0 * &b.p.2;
-    tc_p -= &a.v.1 * &a.p.0 * &c.p.2;
-    tc_p -= &a.v.1 * &a.p.2 * &b.p.0;
-    tc_p += &a.v.1 * &a.p.2 * &c.p.0;
-    tc_p += &a.v.1 * &b.p.0 * &c.p.2;
-    tc_p -= &a.v.1 * &b.p.2 * &c.p.0;
-    tc_p -= &a.v.2 * &a.p.0 * &b.p.1;
-    tc_p += &a.v.2 * &a.p.0 * &c.p.1;
-    tc_p += &a.v.2 * &a.p.1 * &b.p.0;
-    tc_p -= &a.v.2 * &a.p.1 * &c.p.0;
-    tc_p -= &a.v.2 * &b.p.0 * &c.p.1;
-    tc_p += &a.v.2 * &b.p.1 * &c.p.0;
-    tc_p -= &a.p.0 * &b.v.1 * &b.p.2;
-    tc_p += &a.p.0 * &b.v.1 * &c.p.2;
-    tc_p += &a.p.0 * &b.v.2 * &b.p.1;
-    tc_p -= &a.p.0 * &b.v.2 * &c.p.1;
-    tc_p += &a.p.1 * &b.v.0 * &b.p.2;
-    tc_p -= &a.p.1 * &b.v.0 * &c.p.2;
-    tc_p -= &a.p.1 * &b.v.2 * &b.p.0;
-    tc_p += &a.p.1 * &b.v.2 * &c.p.0;
-    tc_p -= &a.p.2 * &b.v.0 * &b.p.1;
-    tc_p += &a.p.2 * &b.v.0 * &c.p.1;
-    tc_p += &a.p.2 * &b.v.1 * &b.p.0;
-    tc_p -= &a.p.2 * &b.v.1 * &c.p.0;
-    tc_p += &b.v.0 * &b.p.1 * &c.p.2;
-    tc_p -= &b.v.0 * &b.p.2 * &c.p.1;
-    tc_p -= &b.v.1 * &b.p.0 * &c.p.2;
-    tc_p += &b.v.1 * &b.p.2 * &c.p.0;
-    tc_p += &b.v.2 * &b.p.0 * &c.p.1;
-    tc_p -= &b.v.2 * &b.p.1 * &c.p.0;
-
-    let mut tc_q = BigInt::from(0);
-    tc_q += &a.v.0 * &a.p.1 * &b.v.2;
-    tc_q -= &a.v.0 * &a.p.1 * &c.v.2;
-    tc_q -= &a.v.0 * &a.p.2 * &b.v.1;
-    tc_q += &a.v.0 * &a.p.2 * &c.v.1;
-    tc_q += &a.v.0 * &b.v.1 * &b.p.2;
-    tc_q -= &a.v.0 * &b.v.2 * &b.p.1;
-    tc_q += &a.v.0 * &b.p.1 * &c.v.2;
-    tc_q -= &a.v.0 * &b.p.2 * &c.v.1;
-    tc_q -= &a.v.1 * &a.p.0 * &b.v.2;
-    tc_q += &a.v.1 * &a.p.0 * &c.v.2;
-    tc_q += &a.v.1 * &a.p.2 * &b.v.0;
-    tc_q -= &a.v.1 * &a.p.2 * &c.v.0;
-    tc_q -= &a.v.1 * &b.v.0 * &b.p.2;
-    tc_q += &a.v.1 * &b.v.2 * &b.p.0;
-    tc_q -= &a.v.1 * &b.p.0 * &c.v.2;
-    tc_q += &a.v.1 * &b.p.2 * &c.v.0;
-    tc_q += &a.v.2 * &a.p.0 * &b.v.1;
-    tc_q -= &a.v.2 * &a.p.0 * &c.v.1;
-    tc_q -= &a.v.2 * &a.p.1 * &b.v.0;
-    tc_q += &a.v.2 * &a.p.1 * &c.v.0;
-    tc_q += &a.v.2 * &b.v.0 * &b.p.1;
-    tc_q -= &a.v.2 * &b.v.1 * &b.p.0;
-    tc_q += &a.v.2 * &b.p.0 * &c.v.1;
-    tc_q -= &a.v.2 * &b.p.1 * &c.v.0;
-    tc_q -= &a.p.0 * &b.v.1 * &c.v.2;
-    tc_q += &a.p.0 * &b.v.2 * &c.v.1;
-    tc_q += &a.p.1 * &b.v.0 * &c.v.2;
-    tc_q -= &a.p.1 * &b.v.2 * &c.v.0;
-    tc_q -= &a.p.2 * &b.v.0 * &c.v.1;
-    tc_q += &a.p.2 * &b.v.1 * &c.v.0;
-    tc_q -= &b.v.0 * &b.p.1 * &c.v.2;
-    tc_q += &b.v.0 * &b.p.2 * &c.v.1;
-    tc_q += &b.v.1 * &b.p.0 * &c.v.2;
-    tc_q -= &b.v.1 * &b.p.2 * &c.v.0;
-    tc_q -= &b.v.2 * &b.p.0 * &c.v.1;
-    tc_q += &b.v.2 * &b.p.1 * &c.v.0;
-
-    let tc = tc_p / tc_q;
-
-    let tb = (&a.v.0*&a.p.1*&tc - &a.v.0*&b.p.1*&tc - &a.v.1*&a.p.0*&tc + &a.v.1*&b.p.0*&tc - &a.p.0*&b.p.1 + &a.p.0*&c.v.1*&tc + &a.p.0*&c.p.1 + &a.p.1*&b.p.0 - &a.p.1*&c.v.0*&tc - &a.p.1*&c.p.0 - &b.p.0*&c.v.1*&tc - &b.p.0*&c.p.1 + &b.p.1*&c.v.0*&tc + &b.p.1*&c.p.0)/(&a.v.0*&a.p.1 + &a.v.0*&b.v.1*&tc - &a.v.0*&c.v.1*&tc - &a.v.0*&c.p.1 - &a.v.1*&a.p.0 - &a.v.1*&b.v.0*&tc + &a.v.1*&c.v.0*&tc + &a.v.1*&c.p.0 + &a.p.0*&b.v.1 - &a.p.1*&b.v.0 + &b.v.0*&c.v.1*&tc + &b.v.0*&c.p.1 - &b.v.1*&c.v.0*&tc - &b.v.1*&c.p.0);
-    let ta = (-&a.p.1*&tb + &a.p.1*&tc - &b.v.1*&tb*&tc - &b.p.1*&tc + &c.v.1*&tb*&tc + &c.p.1*&tb) / (&a.v.1*&tb - &a.v.1*&tc - &b.v.1*&tb - &b.p.1 + &c.v.1*&tc + &c.p.1);
-
-    let (ax, ay, az) = pos_at(a, &ta);
-    let (bx, by, bz) = pos_at(b, &tb);
-    let v = ((&bx - &ax) / (&tb - &ta), (&by - &ay) / (&tb - &ta), (&bz - &az) / (&tb - &ta));
-    let p = (&ax - v.0 * &ta, &ay - v.1 * &ta, &az - v.2 * &ta);
+// The linear system for a rock that hits stones a, b and c.
+fn equations(a: &BigStone, b: &BigStone, c: &BigStone) -> (Matrix<BigRational>, Vec<BigRational>) {
+    // The rock (P, V) hits stone i iff (P - p_i) x (V - v_i) = 0. Expanding that, the P x V term
+    // is the same for every stone, so the difference of two stones' equations is linear:
+    //   P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i
+    // Two pairs of stones give six equations in the six unknowns.
+    let mut rows = vec![];
+    let mut rhs = vec![];
+    for other in [b, c] {
+        // P x w = -(w x P)
+        let p_part = cross_matrix(&(&a.v - &other.v));
+        let v_part = cross_matrix(&(&other.p - &a.p));
+        rows.extend(p_part.into_iter().zip(v_part).map(|(p, v)| p.into_iter().chain(v).collect_vec()));
+        rhs.extend((other.p.cross(&other.v) - a.p.cross(&a.v)).0.map(BigRational::from));
+    }
+    (Matrix::from_int_rows(rows), rhs)
+}
 
-    return [p.0, p.1, p.2].iter().sum::<BigInt>().try_into().unwrap();
+fn part2(stones: &[Stone]) -> isize {
+    // solve would quietly zero any free variables, so only use stones that pin the rock down
+    let big = stones.iter().map(Stone::big).collect_vec();
+    let (m, rhs) = big.iter().tuple_combinations()
+        .map(|(a, b, c)| equations(a, b, c))
+        .find(|(m, _)| m.rank() == 6)
+        .expect("no three hailstones determine the rock");
+    let solution = m.solve(&rhs).expect("no rock hits every hailstone");
+    let total = solution[..3].iter().sum::<BigRational>();
+    assert!(total.is_integer());
+    total.to_integer().try_into().unwrap()
 }
 
 fn main() {
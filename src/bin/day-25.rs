@@ -3,29 +3,22 @@ use array_macro::array;
 use bitvec::bitvec;
 use bitvec::prelude::BitVec;
 use itertools::{Itertools};
-use aoc2023::common::read_input_lines;
+use aoc2023::common::{output_path, read_input_lines};
+use aoc2023::graph::{DotStyle, Graph as Diagram};
+use aoc2023::intern::Alphabetic;
 use rand::{Rng};
 use rand::distributions::{WeightedIndex, Distribution};
 
+type Label = Alphabetic<3>;
+
 #[inline]
 fn idx(s: &str) -> u16 {
-    let s = s.as_bytes();
-    let mut result = 0;
-    for c in s[0..3].iter() {
-        result *= 26;
-        result += (*c - b'a') as u16;
-    }
-    result
+    Label::encode(s).unwrap() as u16
 }
 
 #[inline]
 fn s(i: u16) -> String {
-    format!(
-        "{}{}{}",
-        ((i / (26 * 26)) as u8 + b'a') as char,
-        (((i / 26) % 26) as u8 + b'a') as char,
-        ((i % (26)) as u8 + b'a') as char,
-    )
+    Label::decode(i as u32)
 }
 
 // struct Node {
@@ -37,7 +30,7 @@ fn s(i: u16) -> String {
 //     count: usize
 // }
 
-const SIZE: usize = 26 * 26 * 26;
+const SIZE: usize = Label::SIZE;
 // type Graph = [BitVec; SIZE];
 // type Graph = Vec<[u16; SIZE]>;
 
@@ -239,6 +232,20 @@ fn karger(ggraph: &mut Graph, mut ee_count: u16) {
     }
 }
 
+// the wiring diagram, with each edge weighted by how many wires it stands for
+fn diagram(graph: &Graph) -> Diagram<String, u16> {
+    let mut diagram = Diagram::new();
+    for node in graph.pos_to_idx.iter() {
+        diagram.add_node(s(*node));
+    }
+    for (u, edges) in graph.adj.iter().enumerate() {
+        for (v, count) in edges.iter().enumerate().filter(|(_, c)| **c > 0) {
+            diagram.add_edge(u, v, *count);
+        }
+    }
+    diagram
+}
+
 fn main() {
     let input = read_input_lines().unwrap();
     let mut graph = Graph{
@@ -264,6 +271,10 @@ fn main() {
         }
     }
 
+    if let Some(path) = output_path() {
+        diagram(&graph).write_dot(path, DotStyle::Undirected).unwrap();
+    }
+
     karger(&mut graph, e_count);
     // println!("{:?}", graph[idx("jqt")]);
 }
\ No newline at end of file
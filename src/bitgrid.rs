@@ -0,0 +1,306 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use crate::coord::{Dir, Pt, Point};
+use crate::grid::Grid;
+
+const BITS: usize = u64::BITS as usize;
+
+// A grid of booleans packed one bit per cell. Each row starts on a fresh word so that rows can be
+// operated on (and shifted) independently. Bits past the width of a row are always zero.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    pub width: usize,
+    pub height: usize,
+    words_per_row: usize,
+    data: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(BITS);
+        BitGrid { width, height, words_per_row, data: vec![0; words_per_row * height] }
+    }
+
+    #[inline]
+    fn locate(&self, p: Pt<usize>) -> (usize, u64) {
+        let Point([x, y]) = p;
+        if x >= self.width || y >= self.height {
+            panic!("{} is out of bounds for {}x{} BitGrid", p, self.width, self.height);
+        }
+        (y * self.words_per_row + x / BITS, 1 << (x % BITS))
+    }
+
+    #[inline]
+    pub fn get(&self, p: Pt<usize>) -> bool {
+        let (word, bit) = self.locate(p);
+        self.data[word] & bit != 0
+    }
+
+    #[inline]
+    pub fn set(&mut self, p: Pt<usize>, value: bool) {
+        let (word, bit) = self.locate(p);
+        if value {
+            self.data[word] |= bit;
+        } else {
+            self.data[word] &= !bit;
+        }
+    }
+
+    #[inline]
+    pub fn insert(&mut self, p: Pt<usize>) {
+        self.set(p, true);
+    }
+
+    #[inline]
+    pub fn remove(&mut self, p: Pt<usize>) {
+        self.set(p, false);
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+    }
+
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.data[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[u64]> {
+        // max(1) so that zero-width grids don't upset chunks_exact
+        self.data.chunks_exact(self.words_per_row.max(1))
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.iter().all(|w| *w == 0)
+    }
+
+    pub fn row_count(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn column_count(&self, x: usize) -> usize {
+        let word = x / BITS;
+        let shift = x % BITS;
+        (0..self.height)
+            .map(|y| ((self.data[y * self.words_per_row + word] >> shift) & 1) as usize)
+            .sum()
+    }
+
+    // popcount of every column at once; cheaper than calling column_count width times
+    pub fn column_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.width];
+        for p in self.iter_ones() {
+            counts[p.x()] += 1;
+        }
+        counts
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Pt<usize>> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row(y).iter().enumerate().flat_map(move |(i, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(Pt(i * BITS + bit, y))
+                })
+            })
+        })
+    }
+
+    // clear everything in self which is set in other
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+        for (a, b) in self.data.iter_mut().zip(other.data.iter()) {
+            *a &= !b;
+        }
+    }
+
+    // Move every set bit one cell in the given direction. Bits that would leave the grid are lost,
+    // and empty cells are shifted in behind.
+    pub fn shift(&mut self, dir: Dir) {
+        let wpr = self.words_per_row;
+        if wpr == 0 || self.height == 0 {
+            return;
+        }
+        match dir {
+            Dir::N => {
+                self.data.copy_within(wpr.., 0);
+                let len = self.data.len();
+                self.data[len - wpr..].fill(0);
+            }
+            Dir::S => {
+                let len = self.data.len();
+                self.data.copy_within(..len - wpr, wpr);
+                self.data[..wpr].fill(0);
+            }
+            Dir::E => {
+                for row in self.data.chunks_exact_mut(wpr) {
+                    for i in (0..wpr).rev() {
+                        let carry = if i > 0 { row[i - 1] >> (BITS - 1) } else { 0 };
+                        row[i] = (row[i] << 1) | carry;
+                    }
+                }
+                self.mask_padding();
+            }
+            Dir::W => {
+                for row in self.data.chunks_exact_mut(wpr) {
+                    for i in 0..wpr {
+                        let carry = if i + 1 < wpr { row[i + 1] << (BITS - 1) } else { 0 };
+                        row[i] = (row[i] >> 1) | carry;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn shifted(&self, dir: Dir) -> BitGrid {
+        let mut result = self.clone();
+        result.shift(dir);
+        result
+    }
+
+    // zero the bits past the end of each row, which operations like not and shift E can set
+    fn mask_padding(&mut self) {
+        let used = self.width % BITS;
+        if used == 0 {
+            return;
+        }
+        let mask = (1 << used) - 1;
+        for row in self.data.chunks_exact_mut(self.words_per_row) {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "BitGrid size mismatch: {}x{} vs {}x{}", self.width, self.height, other.width, other.height
+        );
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut result = BitGrid::new(grid.width, grid.height);
+        for (p, value) in grid.enumerate() {
+            if *value {
+                result.insert(p);
+            }
+        }
+        result
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(bits: &BitGrid) -> Self {
+        let mut grid = Grid::new(bits.width, bits.height);
+        for p in bits.iter_ones() {
+            grid[p] = true;
+        }
+        grid
+    }
+}
+
+macro_rules! bitgrid_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $assign_trait<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, other: &BitGrid) {
+                self.assert_same_size(other);
+                for (a, b) in self.data.iter_mut().zip(other.data.iter()) {
+                    *a = *a $op *b;
+                }
+            }
+        }
+
+        impl $trait for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, other: &BitGrid) -> BitGrid {
+                let mut result = self.clone();
+                result.$assign_method(other);
+                result
+            }
+        }
+    };
+}
+
+bitgrid_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bitgrid_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bitgrid_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        for w in result.data.iter_mut() {
+            *w = !*w;
+        }
+        result.mask_padding();
+        result
+    }
+}
+
+impl Debug for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                f.write_str(if self.get(Pt(x, y)) { "#" } else { "." })?;
+            }
+            if y + 1 < self.height {
+                f.write_str("\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// stored as dimensions plus the packed row words
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::Error;
+    use super::{BitGrid, BITS};
+
+    #[derive(Serialize)]
+    struct BitGridRef<'a> {
+        width: usize,
+        height: usize,
+        data: &'a [u64],
+    }
+
+    #[derive(Deserialize)]
+    struct BitGridRepr {
+        width: usize,
+        height: usize,
+        data: Vec<u64>,
+    }
+
+    impl Serialize for BitGrid {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            BitGridRef { width: self.width, height: self.height, data: &self.data }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for BitGrid {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let BitGridRepr { width, height, data } = BitGridRepr::deserialize(deserializer)?;
+            let words_per_row = width.div_ceil(BITS);
+            if data.len() != words_per_row * height {
+                return Err(D::Error::custom(format!(
+                    "{}x{} BitGrid needs {} words but got {}", width, height, words_per_row * height, data.len()
+                )));
+            }
+            let mut result = BitGrid { width, height, words_per_row, data };
+            result.mask_padding();
+            Ok(result)
+        }
+    }
+}
@@ -21,6 +21,11 @@ pub fn read_input() -> Result<String> {
     Ok(input)
 }
 
+// an optional second argument, for days which can write out a picture of what they're doing
+pub fn output_path() -> Option<String> {
+    env::args().nth(2)
+}
+
 pub fn read_input_bytes() -> impl Iterator<Item=u8> {
     let path = env::args().nth(1).expect("No input supplied!");
     let file = File::open(path).expect("Could not open file");
@@ -1,48 +1,647 @@
-use core::ops::Add;
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Div, Mul, Rem, Sub};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, AddAssign, BitAnd, BitOr, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
 use bit_set::BitSet;
+use num::{One, Zero};
+use thiserror::Error;
 use crate::grid::Grid;
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug, PartialOrd, Ord)]
-pub struct Pt<T> (pub T, pub T);
+pub mod hex;
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+// A point (or vector) in N dimensions. Pt and Pt3 are the 2D and 3D ones, and can be built with
+// Pt(x, y) and Pt3(x, y, z) as if they were tuple structs; destructure with Point([x, y]).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+pub type Pt<T> = Point<T, 2>;
+pub type Pt3<T> = Point<T, 3>;
+
+#[allow(non_snake_case)]
+#[inline]
+pub const fn Pt<T>(x: T, y: T) -> Pt<T> {
+    Point([x, y])
+}
+
+#[allow(non_snake_case)]
+#[inline]
+pub const fn Pt3<T>(x: T, y: T, z: T) -> Pt3<T> {
+    Point([x, y, z])
+}
+
+// Compass directions on a grid where y increases going south (i.e. down the screen)
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dir { N, E, S, W }
 
-impl<T: Display> Display for Pt<T> {
+impl Dir {
+    // clockwise from north
+    pub const ALL: [Dir; 4] = [Dir::N, Dir::E, Dir::S, Dir::W];
+
+    #[inline]
+    pub fn turn_right(self) -> Dir {
+        Dir::ALL[(self as usize + 1) % 4]
+    }
+
+    #[inline]
+    pub fn turn_left(self) -> Dir {
+        Dir::ALL[(self as usize + 3) % 4]
+    }
+
+    #[inline]
+    pub fn opposite(self) -> Dir {
+        Dir::ALL[(self as usize + 2) % 4]
+    }
+
+    // The direction something travelling in this direction leaves a / mirror in
+    pub fn reflect_slash(self) -> Dir {
+        match self {
+            Dir::N => Dir::E,
+            Dir::E => Dir::N,
+            Dir::S => Dir::W,
+            Dir::W => Dir::S,
+        }
+    }
+
+    // ...and a \ mirror
+    pub fn reflect_backslash(self) -> Dir {
+        match self {
+            Dir::N => Dir::W,
+            Dir::E => Dir::S,
+            Dir::S => Dir::E,
+            Dir::W => Dir::N,
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Dir::N | Dir::S)
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !self.is_vertical()
+    }
+
+    // One step in this direction
+    pub fn delta<T: Zero + One + Neg<Output = T>>(self) -> Pt<T> {
+        match self {
+            Dir::N => Pt(T::zero(), -T::one()),
+            Dir::E => Pt(T::one(), T::zero()),
+            Dir::S => Pt(T::zero(), T::one()),
+            Dir::W => Pt(-T::one(), T::zero()),
+        }
+    }
+
+    // Accepts compass letters, UDLR and arrows (^>v<)
+    pub fn from_char(c: char) -> Option<Dir> {
+        match c {
+            'N' | 'U' | '^' => Some(Dir::N),
+            'E' | 'R' | '>' => Some(Dir::E),
+            'S' | 'D' | 'v' => Some(Dir::S),
+            'W' | 'L' | '<' => Some(Dir::W),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(self) -> char {
+        match self {
+            Dir::N => '^',
+            Dir::E => '>',
+            Dir::S => 'v',
+            Dir::W => '<',
+        }
+    }
+}
+
+impl TryFrom<char> for Dir {
+    type Error = CoordErr;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Dir::from_char(c).ok_or(CoordErr::InvalidDir(c))
+    }
+}
+
+impl Display for Dir {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.0, self.1)
+        f.write_str(match self { Dir::N => "N", Dir::E => "E", Dir::S => "S", Dir::W => "W" })
+    }
+}
+
+// The eight compass directions, clockwise from north
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dir8 { N, NE, E, SE, S, SW, W, NW }
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW];
+
+    // 45° clockwise
+    #[inline]
+    pub fn rotate_right(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 1) % 8]
+    }
+
+    // 45° anticlockwise
+    #[inline]
+    pub fn rotate_left(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 7) % 8]
+    }
+
+    #[inline]
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 2) % 8]
+    }
+
+    #[inline]
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 6) % 8]
+    }
+
+    #[inline]
+    pub fn opposite(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    // One step in this direction; diagonals move one in each axis
+    pub fn delta<T: Zero + One + Neg<Output = T>>(self) -> Pt<T> {
+        let Point([x, y]) = match self {
+            Dir8::N => Pt(0, -1),
+            Dir8::NE => Pt(1, -1),
+            Dir8::E => Pt(1, 0),
+            Dir8::SE => Pt(1, 1),
+            Dir8::S => Pt(0, 1),
+            Dir8::SW => Pt(-1, 1),
+            Dir8::W => Pt(-1, 0),
+            Dir8::NW => Pt(-1, -1),
+        };
+        let unit = |a: i8| match a {
+            1 => T::one(),
+            -1 => -T::one(),
+            _ => T::zero(),
+        };
+        Pt(unit(x), unit(y))
+    }
+
+    // The two cardinal directions making up a diagonal, or just the one for a cardinal
+    pub fn components(self) -> (Dir, Option<Dir>) {
+        match self {
+            Dir8::N => (Dir::N, None),
+            Dir8::NE => (Dir::N, Some(Dir::E)),
+            Dir8::E => (Dir::E, None),
+            Dir8::SE => (Dir::S, Some(Dir::E)),
+            Dir8::S => (Dir::S, None),
+            Dir8::SW => (Dir::S, Some(Dir::W)),
+            Dir8::W => (Dir::W, None),
+            Dir8::NW => (Dir::N, Some(Dir::W)),
+        }
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+impl TryFrom<Dir8> for Dir {
+    type Error = CoordErr;
+
+    fn try_from(dir: Dir8) -> Result<Self, Self::Error> {
+        match dir.components() {
+            (dir, None) => Ok(dir),
+            _ => Err(CoordErr::Diagonal(dir)),
+        }
+    }
+}
+
+impl FromStr for Dir8 {
+    type Err = CoordErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "N" | "n" => Ok(Dir8::N),
+            "NE" | "ne" => Ok(Dir8::NE),
+            "E" | "e" => Ok(Dir8::E),
+            "SE" | "se" => Ok(Dir8::SE),
+            "S" | "s" => Ok(Dir8::S),
+            "SW" | "sw" => Ok(Dir8::SW),
+            "W" | "w" => Ok(Dir8::W),
+            "NW" | "nw" => Ok(Dir8::NW),
+            _ => Err(CoordErr::InvalidDir8(s.to_string())),
+        }
+    }
+}
+
+impl Display for Dir8 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Dir8::N => "N", Dir8::NE => "NE", Dir8::E => "E", Dir8::SE => "SE",
+            Dir8::S => "S", Dir8::SW => "SW", Dir8::W => "W", Dir8::NW => "NW",
+        })
+    }
+}
+
+// A set of Dirs packed into one byte, e.g. for remembering which ways a cell has been crossed
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DirSet(u8);
+
+impl DirSet {
+    pub const EMPTY: DirSet = DirSet(0);
+    pub const ALL: DirSet = DirSet(0b1111);
+
+    pub fn new() -> Self {
+        DirSet::EMPTY
+    }
+
+    #[inline]
+    pub fn contains(self, dir: Dir) -> bool {
+        self.0 & (1 << dir as u8) != 0
+    }
+
+    // true if dir wasn't already present, like HashSet::insert
+    #[inline]
+    pub fn insert(&mut self, dir: Dir) -> bool {
+        let new = !self.contains(dir);
+        self.0 |= 1 << dir as u8;
+        new
+    }
+
+    #[inline]
+    pub fn remove(&mut self, dir: Dir) -> bool {
+        let present = self.contains(dir);
+        self.0 &= !(1 << dir as u8);
+        present
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Dir> {
+        Dir::ALL.into_iter().filter(move |d| self.contains(*d))
+    }
+}
+
+impl From<Dir> for DirSet {
+    fn from(dir: Dir) -> Self {
+        DirSet(1 << dir as u8)
+    }
+}
+
+impl FromIterator<Dir> for DirSet {
+    fn from_iter<I: IntoIterator<Item = Dir>>(iter: I) -> Self {
+        let mut set = DirSet::new();
+        for dir in iter {
+            set.insert(dir);
+        }
+        set
+    }
+}
+
+impl BitOr for DirSet {
+    type Output = DirSet;
+
+    fn bitor(self, other: DirSet) -> DirSet {
+        DirSet(self.0 | other.0)
+    }
+}
+
+impl BitAnd for DirSet {
+    type Output = DirSet;
+
+    fn bitand(self, other: DirSet) -> DirSet {
+        DirSet(self.0 & other.0)
+    }
+}
+
+impl Debug for DirSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[inline]
+fn zip_with<A, B, C, const N: usize>(a: [A; N], b: [B; N], mut f: impl FnMut(A, B) -> C) -> [C; N] {
+    let mut b = b.into_iter();
+    a.map(|x| f(x, b.next().unwrap()))
+}
+
+#[inline]
+fn abs_diff<T: PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+impl<T, const N: usize> Point<T, N> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Point<U, N> {
+        Point(self.0.map(f))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter()
+    }
+}
+
+impl<T: Copy> Pt<T> {
+    #[inline]
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    #[inline]
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+}
+
+impl<T: Copy> Pt3<T> {
+    #[inline]
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    #[inline]
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    #[inline]
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+}
+
+impl<T> Pt3<T> {
+    // drop the z coordinate
+    pub fn xy(self) -> Pt<T> {
+        let Point([x, y, _]) = self;
+        Pt(x, y)
+    }
+}
+
+impl<T: Zero, const N: usize> Point<T, N> {
+    pub fn zero() -> Self {
+        Point(std::array::from_fn(|_| T::zero()))
     }
 }
 
-impl<T: Add<Output = T>> Add for Pt<T> {
-    type Output = Pt<T::Output>;
+impl<T: Clone + Zero + Mul<Output = T>, const N: usize> Point<T, N> {
+    pub fn dot(&self, other: &Self) -> T {
+        self.0.iter().zip(other.0.iter()).fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+    }
+}
+
+impl<T: Clone + Mul<Output = T> + Sub<Output = T>> Pt3<T> {
+    pub fn cross(&self, other: &Self) -> Self {
+        let Point([a0, a1, a2]) = self.clone();
+        let Point([b0, b1, b2]) = other.clone();
+        Pt3(
+            a1.clone() * b2.clone() - a2.clone() * b1.clone(),
+            a2 * b0.clone() - a0.clone() * b2,
+            a0 * b1 - a1 * b0,
+        )
+    }
+}
+
+// Distances work for unsigned coordinates too, since they never subtract the larger from the smaller
+impl<T: Clone + Zero + PartialOrd + Sub<Output = T>, const N: usize> Point<T, N> {
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.0.iter().zip(other.0.iter())
+            .fold(T::zero(), |acc, (a, b)| acc + abs_diff(a.clone(), b.clone()))
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.0.iter().zip(other.0.iter())
+            .map(|(a, b)| abs_diff(a.clone(), b.clone()))
+            .fold(T::zero(), |acc, d| if d > acc { d } else { acc })
+    }
+
+    pub fn norm1(&self) -> T {
+        self.manhattan(&Point::zero())
+    }
 
-    fn add(self, other: Self) -> Self::Output
-    {
-        Pt(self.0 + other.0, self.1 + other.1)
+    pub fn norm_inf(&self) -> T {
+        self.chebyshev(&Point::zero())
     }
 }
 
-impl<T: Sub<Output = T> + Add<Output = T>> Sub for Pt<T> {
-    type Output = Pt<T>;
+impl<T: Ord, const N: usize> Point<T, N> {
+    pub fn component_min(self, other: Self) -> Self {
+        Point(zip_with(self.0, other.0, std::cmp::min))
+    }
 
-    fn sub(self, other: Self) -> Self::Output
-    {
-        Pt(self.0 - other.0, self.1 - other.1)
+    pub fn component_max(self, other: Self) -> Self {
+        Point(zip_with(self.0, other.0, std::cmp::max))
+    }
+}
+
+impl<T: Add<Output = T> + Sub<Output = T>> Pt<T> {
+    // dist steps in direction dir. For unsigned coordinates this panics (in debug) if it would
+    // go negative, just like subtracting by hand would.
+    pub fn walk(self, dir: Dir, dist: T) -> Pt<T> {
+        let Point([x, y]) = self;
+        match dir {
+            Dir::N => Pt(x, y - dist),
+            Dir::E => Pt(x + dist, y),
+            Dir::S => Pt(x, y + dist),
+            Dir::W => Pt(x - dist, y),
+        }
     }
 }
 
-impl<T: Mul<Output = T> + Add + Copy> Pt<T> {
+impl<T: Clone + Mul<Output = T>, const N: usize> Point<T, N> {
     pub fn scale(self, by: T) -> Self {
-        Pt(by * self.0, by * self.1)
+        self * by
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    #[inline]
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+// Element-wise operations between points, including on references so that points of BigInts
+// don't have to be cloned by hand
+macro_rules! point_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt, $assign_op:tt) => {
+        impl<T: $trait<Output = T>, const N: usize> $trait for Point<T, N> {
+            type Output = Point<T, N>;
+
+            #[inline]
+            fn $method(self, other: Self) -> Self::Output {
+                Point(zip_with(self.0, other.0, |a, b| a $op b))
+            }
+        }
+
+        impl<T: Clone + $trait<Output = T>, const N: usize> $trait for &Point<T, N> {
+            type Output = Point<T, N>;
+
+            #[inline]
+            fn $method(self, other: Self) -> Self::Output {
+                self.clone() $op other.clone()
+            }
+        }
+
+        impl<T: $assign_trait, const N: usize> $assign_trait for Point<T, N> {
+            #[inline]
+            fn $assign_method(&mut self, other: Self) {
+                for (a, b) in self.0.iter_mut().zip(other.0) {
+                    *a $assign_op b;
+                }
+            }
+        }
+    };
+}
+
+point_op!(Add, add, AddAssign, add_assign, +, +=);
+point_op!(Sub, sub, SubAssign, sub_assign, -, -=);
+
+// Scaling by a single value
+macro_rules! point_scalar_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt, $assign_op:tt) => {
+        impl<T: Clone + $trait<Output = T>, const N: usize> $trait<T> for Point<T, N> {
+            type Output = Point<T, N>;
+
+            #[inline]
+            fn $method(self, by: T) -> Self::Output {
+                self.map(|a| a $op by.clone())
+            }
+        }
+
+        impl<T: Clone + $trait<Output = T>, const N: usize> $trait<&T> for &Point<T, N> {
+            type Output = Point<T, N>;
+
+            #[inline]
+            fn $method(self, by: &T) -> Self::Output {
+                self.clone() $op by.clone()
+            }
+        }
+
+        impl<T: Clone + $assign_trait, const N: usize> $assign_trait<T> for Point<T, N> {
+            #[inline]
+            fn $assign_method(&mut self, by: T) {
+                for a in self.0.iter_mut() {
+                    *a $assign_op by.clone();
+                }
+            }
+        }
+    };
+}
+
+point_scalar_op!(Mul, mul, MulAssign, mul_assign, *, *=);
+point_scalar_op!(Div, div, DivAssign, div_assign, /, /=);
+
+impl<T: Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|a| -a)
+    }
+}
+
+impl<T: Clone + Neg<Output = T>, const N: usize> Neg for &Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+impl<T: Display, const N: usize> Display for Point<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(")?;
+        for (i, a) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", a)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for Point<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut t = f.debug_tuple(match N { 2 => "Pt", 3 => "Pt3", _ => "Point" });
+        for a in self.0.iter() {
+            t.field(a);
+        }
+        t.finish()
+    }
+}
+
+// Hashing the array directly would also hash its length, which is a waste for a fixed size
+impl<T: Hash, const N: usize> Hash for Point<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for a in self.0.iter() {
+            a.hash(state);
+        }
+    }
+}
+
+impl<T: Default, const N: usize> Default for Point<T, N> {
+    fn default() -> Self {
+        Point(std::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(a: [T; N]) -> Self {
+        Point(a)
+    }
+}
+
+impl<T, const N: usize> From<Point<T, N>> for [T; N] {
+    fn from(p: Point<T, N>) -> Self {
+        p.0
+    }
+}
+
+impl<T> From<(T, T)> for Pt<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Pt(x, y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Pt3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Pt3(x, y, z)
+    }
+}
+
+impl<T> From<Pt<T>> for (T, T) {
+    fn from(Point([x, y]): Pt<T>) -> Self {
+        (x, y)
+    }
+}
+
+impl<T> From<Pt3<T>> for (T, T, T) {
+    fn from(Point([x, y, z]): Pt3<T>) -> Self {
+        (x, y, z)
     }
 }
 
 impl Pt<usize> {
     pub fn neighbours4(self) -> Vec<Self> {
-        let Pt(x, y) = self;
+        let Point([x, y]) = self;
         let mut result = vec![];
         if x > 0 {
             result.push(Pt(x - 1, y));
@@ -56,7 +655,7 @@ impl Pt<usize> {
     }
 
     pub fn neighbours8(self) -> Vec<Self> {
-        let Pt(x, y) = self;
+        let Point([x, y]) = self;
         let mut result = vec![];
         if y > 0 {
             if x > 0 {
@@ -80,7 +679,7 @@ impl Pt<usize> {
 
 impl Pt<isize> {
     pub fn neighbours4(self) -> [Self; 4] {
-        let Pt(x, y) = self;
+        let Point([x, y]) = self;
         [
             Pt(x - 1, y    ),
             Pt(x + 1, y    ),
@@ -89,7 +688,7 @@ impl Pt<isize> {
         ]
     }
     pub fn neighbours8(self) -> [Self; 8] {
-        let Pt(x, y) = self;
+        let Point([x, y]) = self;
         [
             Pt(x - 1, y - 1),
             Pt(x    , y - 1),
@@ -102,29 +701,57 @@ impl Pt<isize> {
             Pt(x + 1, y + 1),
         ]
     }
+}
 
-    pub fn walk(&self, dir: Dir, dist: isize) -> Pt<isize> {
-        match dir {
-            Dir::N => Pt(self.0, self.1 - dist),
-            Dir::E => Pt(self.0 + dist, self.1),
-            Dir::S => Pt(self.0, self.1 + dist),
-            Dir::W => Pt(self.0 - dist, self.1),
-        }
-    }
+#[derive(Debug, Error)]
+pub enum CoordErr {
+    #[error("{0} has a negative coordinate")]
+    Negative(Pt<isize>),
+    #[error("{0:?} is not a direction")]
+    InvalidDir(char),
+    #[error("{0:?} is not a direction")]
+    InvalidDir8(String),
+    #[error("{0:?} is not a hex direction")]
+    InvalidHexDir(String),
+    #[error("{0} is diagonal")]
+    Diagonal(Dir8),
+    #[error("{0} doesn't fit in an isize")]
+    TooBig(Pt<usize>),
 }
 
 // HAHAHA RUST
-impl From<Pt<isize>> for Pt<usize>
+impl TryFrom<Pt<isize>> for Pt<usize>
 {
-    fn from(other: Pt<isize>) -> Self {
-        Pt(other.0 as usize, other.1 as usize)
+    type Error = CoordErr;
+
+    fn try_from(other: Pt<isize>) -> Result<Self, Self::Error> {
+        let Point([x, y]) = other;
+        if x < 0 || y < 0 {
+            Err(CoordErr::Negative(other))
+        } else {
+            Ok(Pt(x as usize, y as usize))
+        }
     }
 }
 
-impl From<Pt<usize>> for Pt<isize>
+impl TryFrom<Pt<usize>> for Pt<isize>
 {
-    fn from(other: Pt<usize>) -> Self {
-        Pt(other.0 as isize, other.1 as isize)
+    type Error = CoordErr;
+
+    fn try_from(other: Pt<usize>) -> Result<Self, Self::Error> {
+        let Point([x, y]) = other;
+        match (isize::try_from(x), isize::try_from(y)) {
+            (Ok(x), Ok(y)) => Ok(Pt(x, y)),
+            _ => Err(CoordErr::TooBig(other)),
+        }
+    }
+}
+
+impl Pt<usize> {
+    // No allocation can be bigger than isize::MAX bytes, so any index into a grid fits in an
+    // isize. Panics if it doesn't; use try_into for points that might not be grid indices.
+    pub fn signed(self) -> Pt<isize> {
+        self.try_into().expect("point out of isize range")
     }
 }
 // impl<T: RangeBounds<T>> RangeBounds<T> for Pt<T> {
@@ -164,12 +791,12 @@ where T: Rem<Output=T> + Copy + Div<Output=T>,
 impl PointSet<usize> {
     #[inline]
     pub fn insert(&mut self, p: Pt<usize>) {
-        self.storage.insert(p.0 + p.1 * self.width);
+        self.storage.insert(p.x() + p.y() * self.width);
     }
 
     #[inline]
     pub fn contains(&self, p: Pt<usize>) -> bool {
-        self.storage.contains(p.0 + p.1 * self.width)
+        self.storage.contains(p.x() + p.y() * self.width)
     }
 
     pub fn iter<'a>(&'a self) -> impl Iterator<Item=Pt<usize>> + 'a {
@@ -200,3 +827,72 @@ impl Debug for PointSet<usize>
         Ok(())
     }
 }
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt::Formatter;
+    use std::marker::PhantomData;
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::ser::SerializeTuple;
+    use super::{Point, PointSet};
+
+    // Points are sequences of their coordinates, the same as the tuples they look like
+    impl<T: Serialize, const N: usize> Serialize for Point<T, N> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut tuple = serializer.serialize_tuple(N)?;
+            for a in self.0.iter() {
+                tuple.serialize_element(a)?;
+            }
+            tuple.end()
+        }
+    }
+
+    struct PointVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for PointVisitor<T, N> {
+        type Value = Point<T, N>;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "a tuple of {} coordinates", N)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut coords = Vec::with_capacity(N);
+            for i in 0..N {
+                coords.push(seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?);
+            }
+            Ok(Point(coords.try_into().ok().unwrap()))
+        }
+    }
+
+    // read back the same fixed-length tuple shape that serialize writes, which matters for formats
+    // like bincode that don't record lengths
+    impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for Point<T, N> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_tuple(N, PointVisitor(PhantomData))
+        }
+    }
+
+    // PointSets are stored as their width plus the indices of the points in them
+    #[derive(Serialize, Deserialize)]
+    struct PointSetRepr {
+        width: usize,
+        points: Vec<usize>,
+    }
+
+    impl Serialize for PointSet<usize> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            PointSetRepr { width: self.width, points: self.storage.iter().collect() }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PointSet<usize> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = PointSetRepr::deserialize(deserializer)?;
+            let mut result = PointSet::new(repr.width);
+            result.storage.extend(repr.points);
+            Ok(result)
+        }
+    }
+}
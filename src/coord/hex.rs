@@ -0,0 +1,181 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+use super::{CoordErr, Pt3};
+
+// Hexagonal grid coordinates in axial form: q and r, with the third cube coordinate s = -q - r
+// implied. Hexes are plain values, so a hex map is just a HashMap<Hex, T> (or a set of Hexes).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+// Directions named for pointy-topped hexes, anticlockwise from east. Flat-topped grids use the
+// same six neighbours, just with different names: see HexDir::from_flat_str.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HexDir { E, NE, NW, W, SW, SE }
+
+impl HexDir {
+    pub const ALL: [HexDir; 6] = [HexDir::E, HexDir::NE, HexDir::NW, HexDir::W, HexDir::SW, HexDir::SE];
+
+    // 60° anticlockwise
+    #[inline]
+    pub fn rotate_left(self) -> HexDir {
+        HexDir::ALL[(self as usize + 1) % 6]
+    }
+
+    // 60° clockwise
+    #[inline]
+    pub fn rotate_right(self) -> HexDir {
+        HexDir::ALL[(self as usize + 5) % 6]
+    }
+
+    #[inline]
+    pub fn opposite(self) -> HexDir {
+        HexDir::ALL[(self as usize + 3) % 6]
+    }
+
+    pub fn delta(self) -> Hex {
+        match self {
+            HexDir::E => Hex::new(1, 0),
+            HexDir::NE => Hex::new(1, -1),
+            HexDir::NW => Hex::new(0, -1),
+            HexDir::W => Hex::new(-1, 0),
+            HexDir::SW => Hex::new(-1, 1),
+            HexDir::SE => Hex::new(0, 1),
+        }
+    }
+
+    // n, ne, se, s, sw, nw as used for flat-topped hexes
+    pub fn from_flat_str(s: &str) -> Result<HexDir, CoordErr> {
+        match s {
+            "n" | "N" => Ok(HexDir::NW),
+            "ne" | "NE" => Ok(HexDir::NE),
+            "se" | "SE" => Ok(HexDir::E),
+            "s" | "S" => Ok(HexDir::SE),
+            "sw" | "SW" => Ok(HexDir::SW),
+            "nw" | "NW" => Ok(HexDir::W),
+            _ => Err(CoordErr::InvalidHexDir(s.to_string())),
+        }
+    }
+}
+
+// e, ne, nw, w, sw, se as used for pointy-topped hexes
+impl FromStr for HexDir {
+    type Err = CoordErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "e" | "E" => Ok(HexDir::E),
+            "ne" | "NE" => Ok(HexDir::NE),
+            "nw" | "NW" => Ok(HexDir::NW),
+            "w" | "W" => Ok(HexDir::W),
+            "sw" | "SW" => Ok(HexDir::SW),
+            "se" | "SE" => Ok(HexDir::SE),
+            _ => Err(CoordErr::InvalidHexDir(s.to_string())),
+        }
+    }
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub const fn new(q: isize, r: isize) -> Self {
+        Hex { q, r }
+    }
+
+    #[inline]
+    pub fn s(self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(self) -> Pt3<isize> {
+        Pt3(self.q, self.r, self.s())
+    }
+
+    // the coordinates of a cube point must sum to zero
+    pub fn from_cube(p: Pt3<isize>) -> Option<Self> {
+        (p.x() + p.y() + p.z() == 0).then_some(Hex::new(p.x(), p.y()))
+    }
+
+    pub fn walk(self, dir: HexDir, dist: isize) -> Hex {
+        self + dir.delta() * dist
+    }
+
+    pub fn neighbour(self, dir: HexDir) -> Hex {
+        self + dir.delta()
+    }
+
+    pub fn neighbours(self) -> [Hex; 6] {
+        HexDir::ALL.map(|dir| self.neighbour(dir))
+    }
+
+    // number of steps from the origin
+    pub fn length(self) -> isize {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    pub fn distance(self, other: Hex) -> isize {
+        (self - other).length()
+    }
+
+    // Every hex exactly radius steps away, going anticlockwise from the one directly south-west
+    pub fn ring(self, radius: usize) -> impl Iterator<Item = Hex> {
+        let start = self.walk(HexDir::SW, radius as isize);
+        let count = if radius == 0 { 1 } else { 6 * radius };
+        (0..count).scan(start, move |hex, i| {
+            let current = *hex;
+            // walk along each of the six sides in turn, starting by heading east
+            if let Some(side) = i.checked_div(radius) {
+                *hex = hex.neighbour(HexDir::ALL[side]);
+            }
+            Some(current)
+        })
+    }
+
+    // Every hex within radius steps, ring by ring outwards
+    pub fn spiral(self, radius: usize) -> impl Iterator<Item = Hex> {
+        (0..=radius).flat_map(move |r| self.ring(r))
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}, {}>", self.q, self.r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Mul<isize> for Hex {
+    type Output = Hex;
+
+    fn mul(self, by: isize) -> Hex {
+        Hex::new(self.q * by, self.r * by)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
@@ -0,0 +1,96 @@
+use std::hash::Hash;
+use rustc_hash::FxHashMap;
+
+// The shape of x0, f(x0), f(f(x0)), ... for a step function f over a finite state space: the
+// first mu states are a tail that's never seen again, then the sequence loops every lambda states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    // the index in 0..mu + lambda of the state that's the same as the nth
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+}
+
+// Brent's algorithm. Only ever holds a couple of states, but calls f roughly 2mu + 3lambda times,
+// so prefer find_hashed when states are small and steps are expensive.
+pub fn brent<T: Clone + Eq>(x0: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+    // find lambda by racing the hare ahead in ever doubling bursts
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = f(&hare);
+        lambda += 1;
+    }
+
+    // then walk two pointers lambda apart from the start until they meet
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..lambda {
+        hare = f(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        mu += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+// Steps until a state repeats, remembering all of them. Returns the cycle along with the first
+// mu + lambda states, so history[cycle.reduce(n)] is the nth.
+pub fn find_hashed<T: Clone + Eq + Hash>(x0: T, f: impl FnMut(&T) -> T) -> (Cycle, Vec<T>) {
+    match walk(x0, f, None) {
+        Walk::Cycle(cycle, history) => (cycle, history),
+        Walk::Reached(_) => unreachable!(),
+    }
+}
+
+// The state after n steps, skipping round the loop once one turns up. One call for "what does it
+// look like after a billion iterations" puzzles.
+pub fn nth_state<T: Clone + Eq + Hash>(x0: T, f: impl FnMut(&T) -> T, n: usize) -> T {
+    match walk(x0, f, Some(n)) {
+        Walk::Cycle(cycle, mut history) => history.swap_remove(cycle.reduce(n)),
+        Walk::Reached(state) => state,
+    }
+}
+
+enum Walk<T> {
+    Cycle(Cycle, Vec<T>),
+    Reached(T),
+}
+
+fn walk<T: Clone + Eq + Hash>(x0: T, mut f: impl FnMut(&T) -> T, stop_at: Option<usize>) -> Walk<T> {
+    let mut seen = FxHashMap::default();
+    let mut history = vec![];
+    let mut state = x0;
+    loop {
+        if stop_at == Some(history.len()) {
+            return Walk::Reached(state);
+        }
+        if let Some(&mu) = seen.get(&state) {
+            let lambda = history.len() - mu;
+            return Walk::Cycle(Cycle { mu, lambda }, history);
+        }
+        let next = f(&state);
+        seen.insert(state.clone(), history.len());
+        history.push(state);
+        state = next;
+    }
+}
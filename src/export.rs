@@ -0,0 +1,116 @@
+use std::fs::{create_dir_all, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use anyhow::{bail, Result};
+use crate::grid::Grid;
+
+pub type Rgb = [u8; 3];
+
+// An RGB image, for looking at grids that are too big to read as text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    // Each cell of the grid becomes a scale x scale block of pixels coloured by colour.
+    pub fn render<T>(grid: &Grid<T>, scale: usize, colour: impl Fn(&T) -> Rgb) -> Image {
+        let width = grid.width * scale;
+        let height = grid.height * scale;
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for row in grid.rows() {
+            let colours = row.iter().map(&colour).collect::<Vec<_>>();
+            for _ in 0..scale {
+                for c in colours.iter() {
+                    for _ in 0..scale {
+                        pixels.extend_from_slice(c);
+                    }
+                }
+            }
+        }
+        Image { width, height, pixels }
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn write_ppm(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        write!(file, "P6\n{} {}\n255\n", self.width, self.height)?;
+        file.write_all(&self.pixels)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn write_png(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+// A sequence of frames, e.g. one per step of a simulation. All frames must be the same size.
+#[derive(Clone, Debug, Default)]
+pub struct Animation {
+    pub frames: Vec<Image>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Animation { frames: vec![] }
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        if let Some(first) = self.frames.first() {
+            assert!(
+                first.width == frame.width && first.height == frame.height,
+                "frame is {}x{} but animation is {}x{}", frame.width, frame.height, first.width, first.height
+            );
+        }
+        self.frames.push(frame);
+    }
+
+    pub fn record<T>(&mut self, grid: &Grid<T>, scale: usize, colour: impl Fn(&T) -> Rgb) {
+        self.push(Image::render(grid, scale, colour));
+    }
+
+    // Writes frame-00000.png, frame-00001.png, ... into dir
+    pub fn write_pngs(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        create_dir_all(dir)?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            frame.write_png(dir.join(format!("frame-{:05}.png", i)))?;
+        }
+        Ok(())
+    }
+
+    // delay is in hundredths of a second, per the GIF format
+    pub fn write_gif(&self, path: impl AsRef<Path>, delay: u16) -> Result<()> {
+        let Some(first) = self.frames.first() else {
+            bail!("Can't write an animation with no frames");
+        };
+        if first.width > u16::MAX as usize || first.height > u16::MAX as usize {
+            bail!("{}x{} is too big for a GIF", first.width, first.height);
+        }
+        let (width, height) = (first.width as u16, first.height as u16);
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for image in self.frames.iter() {
+            // speed 10 is the default trade-off between quantisation quality and time
+            let mut frame = gif::Frame::from_rgb_speed(width, height, &image.pixels, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use num::{Integer, Num};
+use crate::coord::{Dir, Point, Pt};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Winding {
+    // as seen on screen, i.e. with y increasing downwards
+    Clockwise,
+    Anticlockwise,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+// A simple (non-self-intersecting) polygon with integer vertices. The last vertex joins back up
+// to the first, so it shouldn't be repeated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Pt<isize>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Pt<isize>>) -> Self {
+        Polygon { vertices }
+    }
+
+    // Follow the moves from start, e.g. a dig plan. The path is expected to come back to start.
+    pub fn from_moves(start: Pt<isize>, moves: impl IntoIterator<Item = (Dir, isize)>) -> Self {
+        let mut vertices = vec![start];
+        let mut cur = start;
+        for (dir, dist) in moves {
+            cur = cur.walk(dir, dist);
+            vertices.push(cur);
+        }
+        if vertices.len() > 1 && vertices.last() == vertices.first() {
+            vertices.pop();
+        }
+        Polygon { vertices }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Pt<isize>, Pt<isize>)> + '_ {
+        self.vertices.iter().copied().zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    // Shoelace formula. Doubled so that it's exact; positive for clockwise (on screen) polygons.
+    pub fn double_signed_area(&self) -> isize {
+        self.edges().map(|(Point([x1, y1]), Point([x2, y2]))| x1 * y2 - x2 * y1).sum()
+    }
+
+    // Exact when the doubled area is even, which it always is for polygons with only horizontal
+    // and vertical edges. Otherwise this rounds down.
+    pub fn area(&self) -> isize {
+        self.double_signed_area().abs() / 2
+    }
+
+    // None if the polygon has no area
+    pub fn winding(&self) -> Option<Winding> {
+        match self.double_signed_area().signum() {
+            1 => Some(Winding::Clockwise),
+            -1 => Some(Winding::Anticlockwise),
+            _ => None,
+        }
+    }
+
+    // Euclidean length of the edges
+    pub fn perimeter(&self) -> f64 {
+        self.edges().map(|(a, b)| {
+            let Point([dx, dy]) = b - a;
+            ((dx * dx + dy * dy) as f64).sqrt()
+        }).sum()
+    }
+
+    // Number of lattice points on the edges
+    pub fn boundary_points(&self) -> isize {
+        self.edges().map(|(a, b)| {
+            let Point([dx, dy]) = b - a;
+            dx.gcd(&dy)
+        }).sum()
+    }
+
+    // Number of lattice points strictly inside, by Pick's theorem: A = I + B/2 - 1
+    pub fn interior_points(&self) -> isize {
+        (self.double_signed_area().abs() - self.boundary_points()) / 2 + 1
+    }
+
+    // Interior and boundary together, i.e. the number of unit squares covered if each lattice
+    // point is the centre of a square
+    pub fn lattice_points(&self) -> isize {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn locate(&self, p: Pt<isize>) -> Location {
+        let Point([px, py]) = p;
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (Point([x1, y1]), Point([x2, y2])) = (a, b);
+            let cross = (x2 - x1) * (py - y1) - (y2 - y1) * (px - x1);
+            if cross == 0 && px >= x1.min(x2) && px <= x1.max(x2) && py >= y1.min(y2) && py <= y1.max(y2) {
+                return Location::Boundary;
+            }
+            // Cast a ray east from p and count the edges it crosses. Each edge includes its lower
+            // end but not its upper so that passing through a vertex is only counted once.
+            if (y1 > py) != (y2 > py) {
+                // p is left of the edge if cross has the same sign as the edge's direction in y
+                if (cross > 0) == (y2 > y1) {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside { Location::Inside } else { Location::Outside }
+    }
+
+    // inside or on the boundary
+    pub fn contains(&self, p: Pt<isize>) -> bool {
+        self.locate(p) != Location::Outside
+    }
+}
+
+// An axis-aligned box in N dimensions, covering min (inclusive) to max (exclusive) on each axis.
+// A box with max <= min on any axis is empty.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AABox<T, const N: usize> {
+    pub min: Point<T, N>,
+    pub max: Point<T, N>,
+}
+
+impl<T: Num + Copy + Ord, const N: usize> AABox<T, N> {
+    pub fn new(min: Point<T, N>, max: Point<T, N>) -> Self {
+        AABox { min, max }
+    }
+
+    // from corners which are both inside the box, in any order
+    pub fn from_inclusive(a: Point<T, N>, b: Point<T, N>) -> Self {
+        AABox { min: a.component_min(b), max: a.component_max(b).map(|x| x + T::one()) }
+    }
+
+    // the largest point inside the box
+    pub fn inclusive_max(&self) -> Point<T, N> {
+        self.max.map(|x| x - T::one())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|i| self.max[i] <= self.min[i])
+    }
+
+    pub fn volume(&self) -> T {
+        if self.is_empty() {
+            return T::zero();
+        }
+        (0..N).fold(T::one(), |acc, i| acc * (self.max[i] - self.min[i]))
+    }
+
+    pub fn contains(&self, p: &Point<T, N>) -> bool {
+        (0..N).all(|i| self.min[i] <= p[i] && p[i] < self.max[i])
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty() || (0..N).all(|i| self.min[i] <= other.min[i] && other.max[i] <= self.max[i])
+    }
+
+    // None if they don't overlap
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let result = AABox { min: self.min.component_max(other.min), max: self.max.component_min(other.max) };
+        (!result.is_empty()).then_some(result)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersect(other).is_some()
+    }
+
+    // The smallest box containing both
+    pub fn union_bounds(&self, other: &Self) -> Self {
+        AABox { min: self.min.component_min(other.min), max: self.max.component_max(other.max) }
+    }
+
+    pub fn translate(&self, by: Point<T, N>) -> Self {
+        AABox { min: self.min + by, max: self.max + by }
+    }
+
+    // Cut in two along an axis: the part below at, and the part from at upwards. Either can be
+    // None if the cut misses the box.
+    pub fn split(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let mut below = *self;
+        below.max[axis] = below.max[axis].min(at);
+        let mut above = *self;
+        above.min[axis] = above.min[axis].max(at);
+        ((!below.is_empty()).then_some(below), (!above.is_empty()).then_some(above))
+    }
+
+    // The part of self not in other, as up to 2N disjoint boxes
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(hole) = self.intersect(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+        let mut result = vec![];
+        let mut rest = *self;
+        // peel off the slabs either side of the hole one axis at a time
+        for axis in 0..N {
+            let (below, middle) = rest.split(axis, hole.min[axis]);
+            result.extend(below);
+            let (middle, above) = middle.unwrap().split(axis, hole.max[axis]);
+            result.extend(above);
+            rest = middle.unwrap();
+        }
+        result
+    }
+
+    // Keep only the given axes, in the given order
+    pub fn project<const M: usize>(&self, axes: [usize; M]) -> AABox<T, M> {
+        AABox { min: Point(axes.map(|i| self.min[i])), max: Point(axes.map(|i| self.max[i])) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(isize, isize)]) -> Polygon {
+        Polygon::new(vertices.iter().map(|&(x, y)| Pt(x, y)).collect())
+    }
+
+    #[test]
+    fn unit_square() {
+        let square = polygon(&[(0, 0), (1, 0), (1, 1), (0, 1)]);
+        assert_eq!(square.area(), 1);
+        assert_eq!(square.winding(), Some(Winding::Clockwise));
+        assert_eq!(square.boundary_points(), 4);
+        assert_eq!(square.interior_points(), 0);
+        assert_eq!(square.lattice_points(), 4);
+    }
+
+    #[test]
+    fn right_triangle() {
+        // the hypotenuse from (4, 0) to (0, 3) has no lattice points between its ends
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 3)]);
+        assert_eq!(triangle.double_signed_area(), 12);
+        assert_eq!(triangle.area(), 6);
+        assert_eq!(triangle.boundary_points(), 8);
+        assert_eq!(triangle.interior_points(), 3);
+        assert_eq!(polygon(&[(0, 0), (0, 3), (4, 0)]).winding(), Some(Winding::Anticlockwise));
+    }
+
+    #[test]
+    fn dig_plan() {
+        // the example from day 18
+        use Dir::*;
+        let moves = [(E, 6), (S, 5), (W, 2), (S, 2), (E, 2), (S, 2), (W, 5),
+                     (N, 2), (W, 1), (N, 2), (E, 2), (N, 3), (W, 2), (N, 2)];
+        let lagoon = Polygon::from_moves(Pt(0, 0), moves);
+        assert_eq!(lagoon.vertices.len(), 14);
+        assert_eq!(lagoon.boundary_points(), 38);
+        assert_eq!(lagoon.interior_points(), 24);
+        assert_eq!(lagoon.lattice_points(), 62);
+    }
+
+    #[test]
+    fn locate() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        for vertex in [Pt(0, 0), Pt(4, 0), Pt(4, 4), Pt(0, 4)] {
+            assert_eq!(square.locate(vertex), Location::Boundary);
+        }
+        for edge in [Pt(2, 0), Pt(4, 1), Pt(3, 4), Pt(0, 3)] {
+            assert_eq!(square.locate(edge), Location::Boundary);
+        }
+        assert_eq!(square.locate(Pt(2, 2)), Location::Inside);
+        assert_eq!(square.locate(Pt(1, 3)), Location::Inside);
+        for outside in [Pt(5, 2), Pt(-1, 2), Pt(2, -1), Pt(5, 5)] {
+            assert_eq!(square.locate(outside), Location::Outside);
+        }
+        // level with the top and bottom edges, so the ray runs through two vertices
+        assert_eq!(square.locate(Pt(-1, 0)), Location::Outside);
+        assert_eq!(square.locate(Pt(-1, 4)), Location::Outside);
+        assert!(square.contains(Pt(4, 2)));
+        assert!(!square.contains(Pt(2, 5)));
+
+        let triangle = polygon(&[(0, 0), (4, 0), (0, 3)]);
+        assert_eq!(triangle.locate(Pt(1, 1)), Location::Inside);
+        assert_eq!(triangle.locate(Pt(3, 1)), Location::Outside);
+        assert_eq!(triangle.locate(Pt(2, 0)), Location::Boundary);
+    }
+}
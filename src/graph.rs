@@ -0,0 +1,164 @@
+use std::fmt::{Display, Write as _};
+use std::fs;
+use std::path::Path;
+use anyhow::Result;
+use petgraph::{EdgeType, Graph as PetGraph};
+use petgraph::graph::{IndexType, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge<E> {
+    pub to: usize,
+    pub weight: E,
+}
+
+// A directed graph with a label on every node and a weight on every edge, kept as one list of
+// outgoing edges per node. Nodes are numbered in the order they're added. An undirected graph is
+// one with every edge added both ways, which is what add_undirected_edge does.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Graph<N, E> {
+    nodes: Vec<N>,
+    adj: Vec<Vec<Edge<E>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DotStyle {
+    Directed,
+    // only draws one of each pair of opposite edges
+    Undirected,
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn new() -> Self {
+        Graph { nodes: vec![], adj: vec![] }
+    }
+
+    pub fn add_node(&mut self, label: N) -> usize {
+        self.nodes.push(label);
+        self.adj.push(vec![]);
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: E) {
+        assert!(to < self.nodes.len(), "edge to nonexistent node {to}");
+        self.adj[from].push(Edge { to, weight });
+    }
+
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: E) where E: Clone {
+        self.add_edge(a, b, weight.clone());
+        self.add_edge(b, a, weight);
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adj.iter().map(|edges| edges.len()).sum()
+    }
+
+    pub fn node(&self, n: usize) -> &N {
+        &self.nodes[n]
+    }
+
+    pub fn node_mut(&mut self, n: usize) -> &mut N {
+        &mut self.nodes[n]
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (usize, &N)> {
+        self.nodes.iter().enumerate()
+    }
+
+    pub fn edges(&self, from: usize) -> impl Iterator<Item = &Edge<E>> {
+        self.adj[from].iter()
+    }
+
+    pub fn all_edges(&self) -> impl Iterator<Item = (usize, &Edge<E>)> {
+        self.adj.iter().enumerate().flat_map(|(from, edges)| edges.iter().map(move |e| (from, e)))
+    }
+
+    pub fn neighbours(&self, from: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adj[from].iter().map(|e| e.to)
+    }
+
+    pub fn out_degree(&self, n: usize) -> usize {
+        self.adj[n].len()
+    }
+
+    // the first edge from -> to, if there is one
+    pub fn edge(&self, from: usize, to: usize) -> Option<&E> {
+        self.adj[from].iter().find(|e| e.to == to).map(|e| &e.weight)
+    }
+
+    pub fn edge_mut(&mut self, from: usize, to: usize) -> Option<&mut E> {
+        self.adj[from].iter_mut().find(|e| e.to == to).map(|e| &mut e.weight)
+    }
+
+    pub fn to_dot(&self, style: DotStyle) -> String where N: Display, E: Display {
+        let (keyword, arrow) = match style {
+            DotStyle::Directed => ("digraph", "->"),
+            DotStyle::Undirected => ("graph", "--"),
+        };
+        let mut dot = format!("{keyword} {{\n");
+        for (n, label) in self.nodes() {
+            writeln!(dot, "    {n} [label=\"{}\"];", escape(label)).unwrap();
+        }
+        for (from, edge) in self.all_edges() {
+            if style == DotStyle::Undirected && edge.to < from {
+                continue;
+            }
+            writeln!(dot, "    {from} {arrow} {} [label=\"{}\"];", edge.to, escape(&edge.weight)).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // Writes the graph for Graphviz, e.g. `dot -Tsvg graph.dot > graph.svg`
+    pub fn write_dot(&self, path: impl AsRef<Path>, style: DotStyle) -> Result<()> where N: Display, E: Display {
+        fs::write(path, self.to_dot(style))?;
+        Ok(())
+    }
+}
+
+fn escape(label: &impl Display) -> String {
+    label.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Node indices carry over in both directions.
+impl<N: Clone, E: Clone> From<&Graph<N, E>> for PetGraph<N, E> {
+    fn from(graph: &Graph<N, E>) -> Self {
+        let mut pg = PetGraph::with_capacity(graph.node_count(), graph.edge_count());
+        for label in graph.nodes.iter() {
+            pg.add_node(label.clone());
+        }
+        for (from, edge) in graph.all_edges() {
+            pg.add_edge(NodeIndex::new(from), NodeIndex::new(edge.to), edge.weight.clone());
+        }
+        pg
+    }
+}
+
+// An undirected petgraph has each of its edges added both ways.
+impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType> From<&PetGraph<N, E, Ty, Ix>> for Graph<N, E> {
+    fn from(pg: &PetGraph<N, E, Ty, Ix>) -> Self {
+        let mut graph = Graph::new();
+        for n in pg.node_indices() {
+            graph.add_node(pg[n].clone());
+        }
+        for e in pg.edge_references() {
+            let (from, to) = (e.source().index(), e.target().index());
+            if pg.is_directed() {
+                graph.add_edge(from, to, e.weight().clone());
+            } else {
+                graph.add_undirected_edge(from, to, e.weight().clone());
+            }
+        }
+        graph
+    }
+}
@@ -1,11 +1,13 @@
-use std::cmp::max;
+use std::cmp::{max, Ordering};
 use std::collections::hash_map::RandomState;
 use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Write};
 use std::hash::{Hash, Hasher};
-use std::ops::{Index, IndexMut};
+use std::ops::{Deref, Index, IndexMut};
 use anyhow::{Result};
+use rustc_hash::{FxHashMap, FxHasher};
 use thiserror::Error;
-use crate::coord::Pt;
+use crate::coord::{Dir, Dir8, Pt, Point};
 
 #[derive(Debug, Clone, Eq)]
 pub struct Grid<T> {
@@ -14,10 +16,21 @@ pub struct Grid<T> {
     data: Vec<T>,
 }
 
+const DIRS4: [(Dir, isize, isize); 4] = [(Dir::N, 0, -1), (Dir::E, 1, 0), (Dir::S, 0, 1), (Dir::W, -1, 0)];
+const OFFSETS8: [(isize, isize); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1,  0),          (1,  0),
+    (-1,  1), (0,  1), (1,  1),
+];
+
 #[derive(Debug, Error)]
 pub enum GridErr {
     #[error("Index out of bounds")]
-    IndexError
+    IndexError,
+    #[error("Invalid character {0:?} at {1}")]
+    ParseError(char, Pt<usize>),
+    #[error("Row {0} has length {1} but the first row has length {2}")]
+    RaggedError(usize, usize, usize),
 }
 
 impl<T> Grid<T> {
@@ -103,7 +116,7 @@ impl<T> Grid<T> {
     }
 
     pub fn get(&self, index: Pt<usize>) -> Result<&T, GridErr> {
-        let Pt(x, y) = index;
+        let Point([x, y]) = index;
         if x >= self.width || y >= self.height {
             Err(GridErr::IndexError)
         } else {
@@ -112,29 +125,30 @@ impl<T> Grid<T> {
     }
 
     pub fn contains(&self, p: Pt<usize>) -> bool {
-        let Pt(x, y) = p;
+        let Point([x, y]) = p;
         x < self.width && y < self.height
     }
 
     // WHO THE FUCK NEEDS FUNCTION OVERLOADING, RIGHT???
     pub fn contains_isize(&self, p: Pt<isize>) -> bool {
-        let Pt(x, y) = p;
+        let Point([x, y]) = p;
         x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
     }
 
+    // max(1) so that zero-width grids (which have no data) give no rows rather than panicking
     pub fn rows(&self) -> impl Iterator<Item = &[T]> {
-        self.data.as_slice().chunks_exact(self.width)
+        self.data.as_slice().chunks_exact(self.width.max(1))
     }
 
     pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
-        self.data.as_mut_slice().chunks_exact_mut(self.width)
+        self.data.as_mut_slice().chunks_exact_mut(self.width.max(1))
     }
 
     pub fn subgrid(&self, top_left: Pt<usize>, bottom_right: Pt<usize>) -> Grid<T>
     where T: Copy
     {
-        let Pt(x1, y1) = top_left;
-        let Pt(x2, y2) = bottom_right;
+        let Point([x1, y1]) = top_left;
+        let Point([x2, y2]) = bottom_right;
         let width = max(0, x2 - x1);
         let height = max(0, y2 - y1);
         let mut data = Vec::with_capacity(width * height);
@@ -179,7 +193,60 @@ impl<T> Grid<T> {
         }
     }
 
-    pub fn to_string(&self, sep: Option<&str>) -> String
+    #[inline]
+    fn step(&self, p: Pt<usize>, dx: isize, dy: isize) -> Option<Pt<usize>> {
+        let x = p.x().checked_add_signed(dx)?;
+        let y = p.y().checked_add_signed(dy)?;
+        (x < self.width && y < self.height).then_some(Pt(x, y))
+    }
+
+    // Orthogonal neighbours of p which are inside the grid, in the order N, E, S, W
+    pub fn neighbours4(&self, p: Pt<usize>) -> impl Iterator<Item = (Pt<usize>, &T)> {
+        self.dir_neighbours4(p).map(|(_, q, x)| (q, x))
+    }
+
+    pub fn dir_neighbours4(&self, p: Pt<usize>) -> impl Iterator<Item = (Dir, Pt<usize>, &T)> {
+        DIRS4.iter().filter_map(move |(dir, dx, dy)|
+            self.step(p, *dx, *dy).map(|q| (*dir, q, &self[q]))
+        )
+    }
+
+    // All eight neighbours of p which are inside the grid, row by row
+    pub fn neighbours8(&self, p: Pt<usize>) -> impl Iterator<Item = (Pt<usize>, &T)> {
+        OFFSETS8.iter().filter_map(move |(dx, dy)|
+            self.step(p, *dx, *dy).map(|q| (q, &self[q]))
+        )
+    }
+
+    // As neighbours8 but clockwise from north, with the direction of each
+    pub fn dir_neighbours8(&self, p: Pt<usize>) -> impl Iterator<Item = (Dir8, Pt<usize>, &T)> {
+        Dir8::ALL.into_iter().filter_map(move |dir| {
+            let d = dir.delta::<isize>();
+            self.step(p, d.x(), d.y()).map(|q| (dir, q, &self[q]))
+        })
+    }
+
+    #[inline]
+    fn signed_offset(&self, p: Pt<isize>) -> Option<usize> {
+        match self.contains_isize(p) {
+            true => Some(p.x() as usize + p.y() as usize * self.width),
+            false => None,
+        }
+    }
+
+    pub fn get_signed(&self, p: Pt<isize>) -> Result<&T, GridErr> {
+        self.try_get_signed(p).ok_or(GridErr::IndexError)
+    }
+
+    pub fn try_get_signed(&self, p: Pt<isize>) -> Option<&T> {
+        self.signed_offset(p).map(|i| &self.data[i])
+    }
+
+    pub fn try_get_signed_mut(&mut self, p: Pt<isize>) -> Option<&mut T> {
+        self.signed_offset(p).map(|i| &mut self.data[i])
+    }
+
+    pub fn join(&self, sep: Option<&str>) -> String
     where T: ToString
     {
         let sep = sep.unwrap_or(" ");
@@ -203,8 +270,8 @@ impl<T: Copy> Grid<T> {
             to_visit.remove(&start);
             self[start] = value;
             visited.insert(start);
-            for neighbour in start.neighbours8() {
-                if self.contains(neighbour) && !visited.contains(&neighbour) && cond(&neighbour) {
+            for (neighbour, _) in self.neighbours8(start) {
+                if !visited.contains(&neighbour) && cond(&neighbour) {
                     to_visit.insert(neighbour);
                 }
             }
@@ -212,11 +279,388 @@ impl<T: Copy> Grid<T> {
     }
 }
 
+impl<T: Clone> Grid<T> {
+    // surround the grid with a border n cells thick
+    pub fn padded(&self, n: usize, value: T) -> Grid<T> {
+        let width = self.width + 2 * n;
+        let mut data = Vec::with_capacity(width * (self.height + 2 * n));
+        data.resize(width * n, value.clone());
+        for row in self.rows() {
+            data.resize(data.len() + n, value.clone());
+            data.extend_from_slice(row);
+            data.resize(data.len() + n, value.clone());
+        }
+        data.resize(width * (self.height + 2 * n), value);
+        Grid::from_data(width, self.height + 2 * n, data)
+    }
+
+    // nx copies across by ny copies down
+    pub fn tile(&self, nx: usize, ny: usize) -> Grid<T> {
+        let mut data = Vec::with_capacity(self.data.len() * nx * ny);
+        for _ in 0..ny {
+            for row in self.rows() {
+                for _ in 0..nx {
+                    data.extend_from_slice(row);
+                }
+            }
+        }
+        Grid::from_data(self.width * nx, self.height * ny, data)
+    }
+
+    // other to the right of self
+    pub fn hconcat(&self, other: &Grid<T>) -> Grid<T> {
+        assert_eq!(self.height, other.height, "can't hconcat grids of different heights");
+        let mut data = Vec::with_capacity(self.data.len() + other.data.len());
+        // by index rather than zipping rows(), which has nothing to zip if either side is 0 wide
+        for y in 0..self.height {
+            data.extend_from_slice(&self.data[y * self.width..(y + 1) * self.width]);
+            data.extend_from_slice(&other.data[y * other.width..(y + 1) * other.width]);
+        }
+        Grid::from_data(self.width + other.width, self.height, data)
+    }
+
+    // other below self
+    pub fn vconcat(&self, other: &Grid<T>) -> Grid<T> {
+        assert_eq!(self.width, other.width, "can't vconcat grids of different widths");
+        let mut data = Vec::with_capacity(self.data.len() + other.data.len());
+        data.extend_from_slice(&self.data);
+        data.extend_from_slice(&other.data);
+        Grid::from_data(self.width, self.height + other.height, data)
+    }
+
+    // Like subgrid (bottom_right is exclusive), but clamped to the grid rather than panicking
+    pub fn crop(&self, top_left: Pt<usize>, bottom_right: Pt<usize>) -> Grid<T> {
+        let x2 = bottom_right.x().min(self.width);
+        let y2 = bottom_right.y().min(self.height);
+        let x1 = top_left.x().min(x2);
+        let y1 = top_left.y().min(y2);
+        let mut data = Vec::with_capacity((x2 - x1) * (y2 - y1));
+        for row in self.rows().skip(y1).take(y2 - y1) {
+            data.extend_from_slice(&row[x1..x2]);
+        }
+        Grid::from_data(x2 - x1, y2 - y1, data)
+    }
+
+    // Remove outer rows and columns in which every cell satisfies pred. Also returns where the
+    // top left of the result was in the original grid. If every cell satisfies pred the result is
+    // an empty 0x0 grid, which is fine to print, pad and so on.
+    pub fn trim_where(&self, pred: impl Fn(&T) -> bool) -> (Grid<T>, Pt<usize>) {
+        let keep = |p: &Pt<usize>| !pred(&self[*p]);
+        let mut kept = self.enumerate().map(|(p, _)| p).filter(keep);
+        let Some(first) = kept.next() else {
+            return (Grid::from_data(0, 0, vec![]), Pt(0, 0));
+        };
+        let (top_left, bottom_right) = kept.fold(
+            (first, Pt(first.x() + 1, first.y() + 1)),
+            |(tl, br), Point([x, y])| (Pt(tl.x().min(x), tl.y().min(y)), Pt(br.x().max(x + 1), br.y().max(y + 1)))
+        );
+        (self.crop(top_left, bottom_right), top_left)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Connectivity { Four, Eight }
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Region {
+    pub label: u32,
+    pub area: usize,
+    // number of cell edges that face a cell outside the region (or the edge of the grid)
+    pub perimeter: usize,
+    // bounding box, with bottom_right exclusive as for Grid::subgrid
+    pub top_left: Pt<usize>,
+    pub bottom_right: Pt<usize>,
+    // whether the region touches the N, E, S, W edges of the grid (index with Dir as usize)
+    pub touches: [bool; 4],
+}
+
+impl Region {
+    pub fn touches_border(&self) -> bool {
+        self.touches.iter().any(|t| *t)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Components {
+    // 0 for cells not in any region, otherwise the region's label
+    pub labels: Grid<u32>,
+    // region with label l is at index l - 1
+    pub regions: Vec<Region>,
+}
+
+impl Components {
+    pub fn region(&self, p: Pt<usize>) -> Option<&Region> {
+        match self.labels[p] {
+            0 => None,
+            label => Some(&self.regions[label as usize - 1]),
+        }
+    }
+}
+
+struct UnionFind {
+    parent: Vec<u32>,
+}
+
+impl UnionFind {
+    fn add(&mut self) -> u32 {
+        let label = self.parent.len() as u32;
+        self.parent.push(label);
+        label
+    }
+
+    fn find(&mut self, mut x: u32) -> u32 {
+        while self.parent[x as usize] != x {
+            // path halving
+            let grandparent = self.parent[self.parent[x as usize] as usize];
+            self.parent[x as usize] = grandparent;
+            x = grandparent;
+        }
+        x
+    }
+
+    fn union(&mut self, a: u32, b: u32) -> u32 {
+        let (a, b) = (self.find(a), self.find(b));
+        let (keep, merge) = if a < b { (a, b) } else { (b, a) };
+        self.parent[merge as usize] = keep;
+        keep
+    }
+}
+
+impl<T> Grid<T> {
+    // Label the connected regions of cells satisfying pred. Two-pass union-find, so it's linear in
+    // the size of the grid regardless of the shape of the regions.
+    pub fn components(&self, connectivity: Connectivity, pred: impl Fn(&T) -> bool) -> Components {
+        let mut labels = Grid::<u32>::new(self.width, self.height);
+        // label 0 is reserved for the background
+        let mut sets = UnionFind { parent: vec![0] };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !pred(&self[Pt(x, y)]) {
+                    continue;
+                }
+                // only look at neighbours that have already been visited
+                let mut prior = [0; 4];
+                if x > 0 {
+                    prior[0] = labels[Pt(x - 1, y)];
+                }
+                if y > 0 {
+                    prior[1] = labels[Pt(x, y - 1)];
+                    if connectivity == Connectivity::Eight {
+                        if x > 0 {
+                            prior[2] = labels[Pt(x - 1, y - 1)];
+                        }
+                        if x + 1 < self.width {
+                            prior[3] = labels[Pt(x + 1, y - 1)];
+                        }
+                    }
+                }
+                let mut label = 0;
+                for other in prior.into_iter().filter(|l| *l != 0) {
+                    label = if label == 0 { sets.find(other) } else { sets.union(label, other) };
+                }
+                if label == 0 {
+                    label = sets.add();
+                }
+                labels[Pt(x, y)] = label;
+            }
+        }
+
+        // compact the labels so they run from 1 with no gaps, and gather statistics
+        let mut compact = vec![0; sets.parent.len()];
+        let mut regions: Vec<Region> = vec![];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let p = Pt(x, y);
+                if labels[p] == 0 {
+                    continue;
+                }
+                let root = sets.find(labels[p]) as usize;
+                if compact[root] == 0 {
+                    regions.push(Region {
+                        label: regions.len() as u32 + 1,
+                        area: 0,
+                        perimeter: 0,
+                        top_left: p,
+                        bottom_right: Pt(x + 1, y + 1),
+                        touches: [false; 4],
+                    });
+                    compact[root] = regions.len() as u32;
+                }
+                labels[p] = compact[root];
+            }
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let label = labels[Pt(x, y)];
+                if label == 0 {
+                    continue;
+                }
+                let region = &mut regions[label as usize - 1];
+                region.area += 1;
+                region.top_left = Pt(region.top_left.x().min(x), region.top_left.y().min(y));
+                region.bottom_right = Pt(region.bottom_right.x().max(x + 1), region.bottom_right.y().max(y + 1));
+                region.touches[0] |= y == 0;
+                region.touches[1] |= x + 1 == self.width;
+                region.touches[2] |= y + 1 == self.height;
+                region.touches[3] |= x == 0;
+
+                let outside = |p: Pt<usize>| labels[p] != label;
+                region.perimeter += [
+                    y == 0 || outside(Pt(x, y - 1)),
+                    x + 1 == self.width || outside(Pt(x + 1, y)),
+                    y + 1 == self.height || outside(Pt(x, y + 1)),
+                    x == 0 || outside(Pt(x - 1, y)),
+                ].into_iter().filter(|edge| *edge).count();
+            }
+        }
+
+        Components { labels, regions }
+    }
+}
+
+// Values that are drawn as a single character in puzzle inputs, so grids of them can be parsed
+// with Grid::parse and printed with Display.
+pub trait GridChar: Sized {
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(&self) -> char;
+}
+
+impl GridChar for char {
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl GridChar for u8 {
+    fn from_char(c: char) -> Option<Self> {
+        c.is_ascii().then_some(c as u8)
+    }
+
+    fn to_char(&self) -> char {
+        *self as char
+    }
+}
+
+impl GridChar for bool {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+impl<T: GridChar> Grid<T> {
+    pub fn parse(s: &str) -> Result<Grid<T>, GridErr> {
+        let mut grid = Grid{height: 0, width: 0, data: Vec::new()};
+        for (y, line) in s.lines().enumerate() {
+            let mut row_len = 0;
+            for (x, c) in line.chars().enumerate() {
+                grid.data.push(T::from_char(c).ok_or(GridErr::ParseError(c, Pt(x, y)))?);
+                row_len += 1;
+            }
+            if y == 0 {
+                grid.width = row_len;
+            } else if row_len != grid.width {
+                return Err(GridErr::RaggedError(y, row_len, grid.width));
+            }
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    pub fn overlay(&self) -> Overlay<'_, T> {
+        Overlay { grid: self, marks: FxHashMap::default() }
+    }
+}
+
+impl<T: GridChar> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                f.write_char('\n')?;
+            }
+            for elt in row {
+                f.write_char(elt.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// A grid with some cells drawn differently, for printing paths and points of interest over a map.
+pub struct Overlay<'a, T> {
+    grid: &'a Grid<T>,
+    marks: FxHashMap<Pt<usize>, char>,
+}
+
+impl<'a, T> Overlay<'a, T> {
+    pub fn points(mut self, points: impl IntoIterator<Item = Pt<usize>>, c: char) -> Self {
+        for p in points {
+            self.marks.insert(p, c);
+        }
+        self
+    }
+
+    // Draw arrows along a path. Consecutive points don't have to be adjacent, but should be in a
+    // straight line; if they aren't, only the points themselves are marked.
+    pub fn path(mut self, path: &[Pt<usize>]) -> Self {
+        let mut last_arrow = 'O';
+        for pair in path.windows(2) {
+            let (Point([x1, y1]), Point([x2, y2])) = (pair[0], pair[1]);
+            let arrow = match (x1.cmp(&x2), y1.cmp(&y2)) {
+                (Ordering::Equal, Ordering::Greater) => '^',
+                (Ordering::Less, Ordering::Equal) => '>',
+                (Ordering::Equal, Ordering::Less) => 'v',
+                (Ordering::Greater, Ordering::Equal) => '<',
+                _ => {
+                    self.marks.insert(pair[0], 'O');
+                    last_arrow = 'O';
+                    continue;
+                }
+            };
+            for y in y1.min(y2)..=y1.max(y2) {
+                for x in x1.min(x2)..=x1.max(x2) {
+                    self.marks.insert(Pt(x, y), arrow);
+                }
+            }
+            last_arrow = arrow;
+        }
+        if let Some(last) = path.last() {
+            self.marks.insert(*last, last_arrow);
+        }
+        self
+    }
+}
+
+impl<'a, T: GridChar> Display for Overlay<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (p, elt) in self.grid.enumerate() {
+            if p.x() == 0 && p.y() > 0 {
+                f.write_char('\n')?;
+            }
+            f.write_char(self.marks.get(&p).copied().unwrap_or_else(|| elt.to_char()))?;
+        }
+        Ok(())
+    }
+}
+
 impl<T> Index<Pt<usize>> for Grid<T> {
     type Output = T;
 
     fn index(&self, index: Pt<usize>) -> &Self::Output {
-        let Pt(x, y) = index;
+        let Point([x, y]) = index;
         if x >= self.width || y >= self.height {
             panic!("{} is out of bounds for {}x{} Grid", index, self.width, self.height);
         }
@@ -226,7 +670,7 @@ impl<T> Index<Pt<usize>> for Grid<T> {
 
 impl<T> IndexMut<Pt<usize>> for Grid<T> {
     fn index_mut(&mut self, index: Pt<usize>) -> &mut Self::Output {
-        let Pt(x, y) = index;
+        let Point([x, y]) = index;
         if x >= self.width || y >= self.height {
             panic!("{} is out of bounds for {}x{} Grid", index, self.width, self.height);
         }
@@ -234,18 +678,161 @@ impl<T> IndexMut<Pt<usize>> for Grid<T> {
     }
 }
 
+impl<T> Index<Pt<isize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Pt<isize>) -> &Self::Output {
+        match self.signed_offset(index) {
+            Some(i) => &self.data[i],
+            None => panic!("{} is out of bounds for {}x{} Grid", index, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Pt<isize>> for Grid<T> {
+    fn index_mut(&mut self, index: Pt<isize>) -> &mut Self::Output {
+        match self.signed_offset(index) {
+            Some(i) => &mut self.data[i],
+            None => panic!("{} is out of bounds for {}x{} Grid", index, self.width, self.height),
+        }
+    }
+}
+
+// (x, y)
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self[Pt(index.0, index.1)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self[Pt(index.0, index.1)]
+    }
+}
+
 impl<T: PartialEq> PartialEq<Self> for Grid<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.width == other.width && self.data == other.data
+        self.width == other.width && self.height == other.height && self.data == other.data
     }
 }
 
 impl<T: Hash> Hash for Grid<T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
         self.data.hash(state)
     }
 }
 
+// ordered by dimensions first, then cells in row-major order
+impl<T: PartialOrd> PartialOrd for Grid<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.width, self.height).cmp(&(other.width, other.height)) {
+            Ordering::Equal => self.data.partial_cmp(&other.data),
+            ord => Some(ord),
+        }
+    }
+}
+
+impl<T: Ord> Ord for Grid<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.width, self.height).cmp(&(other.width, other.height))
+            .then_with(|| self.data.cmp(&other.data))
+    }
+}
+
+#[inline]
+fn mix(mut z: u64) -> u64 {
+    // splitmix64 finaliser
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+// A Grid which keeps a Zobrist-style hash of its contents up to date as it is modified, so that
+// hashing a big grid (e.g. to look it up in a map of previously seen states) doesn't have to visit
+// every cell. There's deliberately no IndexMut: writes go through set, which swaps the old value's
+// contribution to the hash for the new one's, so the hash is always current.
+#[derive(Clone, Debug)]
+pub struct HashedGrid<T> {
+    grid: Grid<T>,
+    hash: u64,
+}
+
+impl<T: Hash> HashedGrid<T> {
+    pub fn new(grid: Grid<T>) -> Self {
+        let hash = grid.data.iter().enumerate().fold(0, |acc, (i, x)| acc ^ Self::cell_hash(i, x));
+        HashedGrid { grid, hash }
+    }
+
+    #[inline]
+    fn cell_hash(i: usize, value: &T) -> u64 {
+        let mut hasher = FxHasher::default();
+        value.hash(&mut hasher);
+        mix(mix(hasher.finish()) ^ i as u64)
+    }
+
+    pub fn zobrist(&self) -> u64 {
+        self.hash
+    }
+
+    // write a cell, returning what was there before
+    pub fn set(&mut self, p: Pt<usize>, value: T) -> T {
+        let Point([x, y]) = p;
+        if x >= self.grid.width || y >= self.grid.height {
+            panic!("{} is out of bounds for {}x{} Grid", p, self.grid.width, self.grid.height);
+        }
+        let i = x + y * self.grid.width;
+        self.hash ^= Self::cell_hash(i, &self.grid.data[i]) ^ Self::cell_hash(i, &value);
+        std::mem::replace(&mut self.grid.data[i], value)
+    }
+
+    pub fn into_inner(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+impl<T> Deref for HashedGrid<T> {
+    type Target = Grid<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.grid
+    }
+}
+
+impl<T: Hash> Index<Pt<usize>> for HashedGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: Pt<usize>) -> &Self::Output {
+        &self.grid[index]
+    }
+}
+
+impl<T: PartialEq + Hash> PartialEq for HashedGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.zobrist() == other.zobrist() && self.grid == other.grid
+    }
+}
+
+impl<T: Eq + Hash> Eq for HashedGrid<T> {}
+
+impl<T: Hash> Hash for HashedGrid<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.grid.width.hash(state);
+        self.grid.height.hash(state);
+        self.zobrist().hash(state);
+    }
+}
+
+impl<T: Hash> From<Grid<T>> for HashedGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        HashedGrid::new(grid)
+    }
+}
+
 // struct Iter<T> {
 //     p: usize,
 // }
@@ -282,4 +869,45 @@ impl<T: Hash> Hash for Grid<T> {
 //         }
 //         Some(val)
 //     }
-// }
\ No newline at end of file
+// }
+
+// Grids are stored as their dimensions plus the cells in row-major order, rather than as nested
+// rows
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::Error;
+    use super::Grid;
+
+    #[derive(Serialize)]
+    struct GridRef<'a, T> {
+        width: usize,
+        height: usize,
+        data: &'a [T],
+    }
+
+    #[derive(Deserialize)]
+    struct GridRepr<T> {
+        width: usize,
+        height: usize,
+        data: Vec<T>,
+    }
+
+    impl<T: Serialize> Serialize for Grid<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            GridRef { width: self.width, height: self.height, data: &self.data }.serialize(serializer)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Grid<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let GridRepr { width, height, data } = GridRepr::deserialize(deserializer)?;
+            if data.len() != width * height {
+                return Err(D::Error::custom(format!(
+                    "{}x{} Grid needs {} cells but got {}", width, height, width * height, data.len()
+                )));
+            }
+            Ok(Grid { width, height, data })
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::{Index, IndexMut};
+use crate::coord::{Pt, Pt3, Point};
+use crate::grid::Grid;
+
+// below, N, W, E, S, above
+const OFFSETS6: [(isize, isize, isize); 6] = [
+    (0, 0, -1), (0, -1, 0), (-1, 0, 0), (1, 0, 0), (0, 1, 0), (0, 0, 1),
+];
+
+// A dense 3D grid. Stored z-slice by z-slice, so that each slice is a contiguous Grid-shaped chunk.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid3<T> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid3<T> {
+    pub fn new(width: usize, height: usize, depth: usize) -> Grid3<T>
+    where T: Default + Clone
+    {
+        Grid3 { width, height, depth, data: vec![T::default(); width * height * depth] }
+    }
+
+    pub fn from_data(width: usize, height: usize, depth: usize, data: impl Into<Vec<T>>) -> Grid3<T> {
+        let data = data.into();
+        assert_eq!(data.len(), width * height * depth, "wrong amount of data for {width}x{height}x{depth} Grid3");
+        Grid3 { width, height, depth, data }
+    }
+
+    #[inline]
+    fn offset(&self, p: Pt3<usize>) -> usize {
+        let Point([x, y, z]) = p;
+        if !self.contains(p) {
+            panic!("{} is out of bounds for {}x{}x{} Grid3", p, self.width, self.height, self.depth);
+        }
+        x + y * self.width + z * self.width * self.height
+    }
+
+    #[inline]
+    fn point(&self, i: usize) -> Pt3<usize> {
+        let layer = self.width * self.height;
+        Pt3(i % self.width, (i % layer) / self.width, i / layer)
+    }
+
+    pub fn contains(&self, p: Pt3<usize>) -> bool {
+        let Point([x, y, z]) = p;
+        x < self.width && y < self.height && z < self.depth
+    }
+
+    pub fn contains_isize(&self, p: Pt3<isize>) -> bool {
+        let Point([x, y, z]) = p;
+        x >= 0 && y >= 0 && z >= 0
+            && (x as usize) < self.width && (y as usize) < self.height && (z as usize) < self.depth
+    }
+
+    pub fn get(&self, p: Pt3<usize>) -> Option<&T> {
+        self.contains(p).then(|| &self.data[self.offset(p)])
+    }
+
+    pub fn get_mut(&mut self, p: Pt3<usize>) -> Option<&mut T> {
+        if self.contains(p) {
+            let i = self.offset(p);
+            Some(&mut self.data[i])
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    // x fastest, then y, then z
+    pub fn enumerate(&self) -> impl Iterator<Item = (Pt3<usize>, &T)> {
+        self.data.iter().enumerate().map(|(i, v)| (self.point(i), v))
+    }
+
+    pub fn enumerate_mut(&mut self) -> impl Iterator<Item = (Pt3<usize>, &mut T)> {
+        let (width, layer) = (self.width, self.width * self.height);
+        self.data.iter_mut().enumerate().map(move |(i, v)| (Pt3(i % width, (i % layer) / width, i / layer), v))
+    }
+
+    // the z-slice as a plain slice, in the same order as Grid's data
+    pub fn layer(&self, z: usize) -> &[T] {
+        let layer = self.width * self.height;
+        &self.data[z * layer..(z + 1) * layer]
+    }
+
+    // The cells at (x, y) from the bottom (z = 0) up
+    pub fn column(&self, x: usize, y: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width && y < self.height, "column ({x}, {y}) is out of bounds");
+        self.data.iter().skip(x + y * self.width).step_by((self.width * self.height).max(1))
+    }
+
+    // Every column, with its (x, y)
+    pub fn columns(&self) -> impl Iterator<Item = (Pt<usize>, impl Iterator<Item = &T>)> {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| (Pt(x, y), self.column(x, y))))
+    }
+
+    #[inline]
+    fn step(&self, p: Pt3<usize>, dx: isize, dy: isize, dz: isize) -> Option<Pt3<usize>> {
+        let q = Pt3(p.x().checked_add_signed(dx)?, p.y().checked_add_signed(dy)?, p.z().checked_add_signed(dz)?);
+        self.contains(q).then_some(q)
+    }
+
+    // Face neighbours of p which are inside the grid
+    pub fn neighbours6(&self, p: Pt3<usize>) -> impl Iterator<Item = (Pt3<usize>, &T)> {
+        OFFSETS6.iter().filter_map(move |(dx, dy, dz)|
+            self.step(p, *dx, *dy, *dz).map(|q| (q, &self[q]))
+        )
+    }
+
+    // Face, edge and corner neighbours of p which are inside the grid
+    pub fn neighbours26(&self, p: Pt3<usize>) -> impl Iterator<Item = (Pt3<usize>, &T)> {
+        (-1..=1).flat_map(|dz| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| (dx, dy, dz))))
+            .filter(|d| *d != (0, 0, 0))
+            .filter_map(move |(dx, dy, dz)| self.step(p, dx, dy, dz).map(|q| (q, &self[q])))
+    }
+}
+
+impl<T: Clone> Grid3<T> {
+    // a copy of one horizontal layer
+    pub fn slice_z(&self, z: usize) -> Grid<T> {
+        Grid::from_data(self.width, self.height, self.layer(z))
+    }
+}
+
+impl<T> Index<Pt3<usize>> for Grid3<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Pt3<usize>) -> &Self::Output {
+        &self.data[self.offset(p)]
+    }
+}
+
+impl<T> IndexMut<Pt3<usize>> for Grid3<T> {
+    #[inline]
+    fn index_mut(&mut self, p: Pt3<usize>) -> &mut Self::Output {
+        let i = self.offset(p);
+        &mut self.data[i]
+    }
+}
@@ -0,0 +1,88 @@
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InternErr {
+    #[error("label {0:?} isn't {1} characters long")]
+    WrongLength(String, usize),
+    #[error("label {0:?} has something other than a lowercase letter in it")]
+    NotAlphabetic(String),
+}
+
+// Hands out dense ids 0, 1, 2, ... to labels in the order they're first seen, so per-label data
+// can live in a Vec instead of a map.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: FxHashMap<String, u32>,
+    labels: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, label: &str) -> u32 {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+        let id = self.labels.len() as u32;
+        self.ids.insert(label.to_owned(), id);
+        self.labels.push(label.to_owned());
+        id
+    }
+
+    pub fn get(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: u32) -> &str {
+        &self.labels[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.labels.iter().enumerate().map(|(id, label)| (id as u32, label.as_str()))
+    }
+}
+
+// The fast path for labels of exactly WIDTH lowercase letters: they're read as base 26 numbers,
+// so ids are stable without a table but sparse, anywhere in 0..SIZE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabetic<const WIDTH: usize>;
+
+impl<const WIDTH: usize> Alphabetic<WIDTH> {
+    pub const SIZE: usize = 26usize.pow(WIDTH as u32);
+    // 26^7 doesn't fit in a u32, so anything wider is a compile error wherever it's used
+    const FITS: () = assert!(WIDTH <= 6, "Alphabetic labels can be at most 6 letters long");
+
+    pub fn encode(label: &str) -> Result<u32, InternErr> {
+        let () = Self::FITS;
+        if !label.bytes().all(|c| c.is_ascii_lowercase()) {
+            return Err(InternErr::NotAlphabetic(label.to_owned()));
+        }
+        if label.len() != WIDTH {
+            return Err(InternErr::WrongLength(label.to_owned(), WIDTH));
+        }
+        Ok(label.bytes().fold(0, |acc, c| acc * 26 + (c - b'a') as u32))
+    }
+
+    pub fn decode(id: u32) -> String {
+        let () = Self::FITS;
+        assert!((id as usize) < Self::SIZE, "{id} is too big for a {WIDTH} letter label");
+        let mut label = vec![0; WIDTH];
+        let mut id = id;
+        for c in label.iter_mut().rev() {
+            *c = b'a' + (id % 26) as u8;
+            id /= 26;
+        }
+        String::from_utf8(label).unwrap()
+    }
+}
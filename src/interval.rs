@@ -0,0 +1,266 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{Add, Range, Sub};
+
+// A set of values stored as sorted, disjoint, half-open ranges. Ranges which touch or overlap are
+// merged as they go in, so two sets with the same members always compare equal.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    // one past the largest member, like Range::end
+    pub fn end(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= x);
+        i < self.ranges.len() && self.ranges[i].start <= x
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // everything from first to last (exclusive) overlaps or touches the new range
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for r in other.ranges.iter() {
+            result.insert(r.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // whichever finishes first can't overlap anything else
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut j = 0;
+        for r in self.ranges.iter() {
+            let mut start = r.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            // cut out every range of other which starts inside this one
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < r.end {
+                if start < other.ranges[k].start {
+                    ranges.push(start..other.ranges[k].start);
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+            if start < r.end {
+                ranges.push(start..r.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T> + Default> IntervalSet<T> {
+    // the number of members, for integer T
+    pub fn total_length(&self) -> T {
+        self.ranges.iter().fold(T::default(), |acc, r| acc + (r.end - r.start))
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut result = IntervalSet::new();
+        for r in iter {
+            result.insert(r);
+        }
+        result
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut result = IntervalSet::new();
+        result.insert(range);
+        result
+    }
+}
+
+impl<T: Debug> Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.ranges.iter()).finish()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Segment<T> {
+    source: Range<T>,
+    dest: T,
+}
+
+// A function which shifts each of a number of disjoint ranges to somewhere else, and leaves
+// everything outside them alone. Like an almanac map in day 5.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PiecewiseMap<T> {
+    // sorted by source start
+    segments: Vec<Segment<T>>,
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> PiecewiseMap<T> {
+    pub fn new() -> Self {
+        PiecewiseMap { segments: vec![] }
+    }
+
+    // Map source to a range of the same length starting at dest. Sources mustn't overlap.
+    pub fn insert(&mut self, source: Range<T>, dest: T) {
+        if source.is_empty() {
+            return;
+        }
+        let i = self.segments.partition_point(|s| s.source.start < source.start);
+        assert!(
+            (i == 0 || self.segments[i - 1].source.end <= source.start)
+                && (i == self.segments.len() || source.end <= self.segments[i].source.start),
+            "overlapping segments in PiecewiseMap"
+        );
+        self.segments.insert(i, Segment { source, dest });
+    }
+
+    // the segment containing x, if any
+    fn segment(&self, x: T) -> Option<&Segment<T>> {
+        let i = self.segments.partition_point(|s| s.source.end <= x);
+        self.segments.get(i).filter(|s| s.source.start <= x)
+    }
+
+    #[inline]
+    fn shift(segment: &Segment<T>, x: T) -> T {
+        segment.dest + (x - segment.source.start)
+    }
+
+    pub fn get(&self, x: T) -> T {
+        match self.segment(x) {
+            Some(segment) => Self::shift(segment, x),
+            None => x,
+        }
+    }
+
+    // The image of a range, as one range per piece it's cut into
+    pub fn map_range(&self, range: Range<T>) -> Vec<Range<T>> {
+        let mut result = vec![];
+        let mut start = range.start;
+        let mut i = self.segments.partition_point(|s| s.source.end <= start);
+        while start < range.end {
+            match self.segments.get(i) {
+                Some(segment) if segment.source.start <= start => {
+                    let end = segment.source.end.min(range.end);
+                    result.push(Self::shift(segment, start)..Self::shift(segment, end));
+                    start = end;
+                    i += 1;
+                }
+                // in a gap before the next segment (or after the last one)
+                next => {
+                    let end = next.map_or(range.end, |s| s.source.start.min(range.end));
+                    result.push(start..end);
+                    start = end;
+                }
+            }
+        }
+        result
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges().iter().flat_map(|r| self.map_range(r.clone())).collect()
+    }
+
+    // The smallest value any of range is sent to
+    pub fn min_image(&self, range: Range<T>) -> Option<T> {
+        self.map_range(range).into_iter().map(|r| r.start).min()
+    }
+
+    // x => then.get(self.get(x)), as a single map
+    pub fn compose(&self, then: &PiecewiseMap<T>) -> PiecewiseMap<T> {
+        // The result is a shift on every piece between consecutive cuts, where the cuts are our own
+        // segment boundaries and everything we send to one of then's boundaries. Beyond the cuts
+        // at either end, both maps are the identity.
+        let mut cuts = vec![];
+        for s in self.segments.iter() {
+            cuts.push(s.source.start);
+            cuts.push(s.source.end);
+        }
+        for boundary in then.segments.iter().flat_map(|s| [s.source.start, s.source.end]) {
+            if self.segment(boundary).is_none() {
+                cuts.push(boundary);
+            }
+            for s in self.segments.iter() {
+                let len = s.source.end - s.source.start;
+                if s.dest <= boundary && boundary < s.dest + len {
+                    cuts.push(s.source.start + (boundary - s.dest));
+                }
+            }
+        }
+        cuts.sort();
+        cuts.dedup();
+
+        let mut result = PiecewiseMap::new();
+        for pair in cuts.windows(2) {
+            let dest = then.get(self.get(pair[0]));
+            if dest != pair[0] {
+                result.segments.push(Segment { source: pair[0]..pair[1], dest });
+            }
+        }
+        result
+    }
+
+    // Only possible when the map is a bijection, i.e. the segments' sources and destinations
+    // cover exactly the same values (if destinations overlapped, they'd cover fewer)
+    pub fn invert(&self) -> Option<PiecewiseMap<T>> {
+        let sources: IntervalSet<T> = self.segments.iter().map(|s| s.source.clone()).collect();
+        let dests: IntervalSet<T> = self.segments.iter()
+            .map(|s| s.dest..Self::shift(s, s.source.end))
+            .collect();
+        if sources != dests {
+            return None;
+        }
+        let mut result = PiecewiseMap::new();
+        for s in self.segments.iter() {
+            result.insert(s.dest..Self::shift(s, s.source.end), s.source.start);
+        }
+        Some(result)
+    }
+}
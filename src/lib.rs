@@ -1,4 +1,18 @@
+pub mod bitgrid;
 pub mod common;
 
 pub mod coord;
-pub mod grid;
\ No newline at end of file
+pub mod cycle;
+pub mod export;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod grid3;
+pub mod intern;
+pub mod interval;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod poly;
+pub mod prefix_sum;
+pub mod search;
\ No newline at end of file
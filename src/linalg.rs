@@ -0,0 +1,186 @@
+use std::ops::{Index, IndexMut};
+use num::{BigInt, BigRational, Num};
+
+// A small dense row-major matrix. The elimination routines need exact division to be of any use,
+// so in practice T is BigRational (or some other Ratio).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    pub rows: usize,
+    pub cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Clone + Num> Matrix<T> {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Matrix { rows, cols, data: vec![T::zero(); rows * cols] }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::new(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let mut data = vec![];
+        let mut n_rows = 0;
+        for row in rows {
+            data.extend(row);
+            n_rows += 1;
+        }
+        let cols = data.len().checked_div(n_rows).unwrap_or(0);
+        assert_eq!(cols * n_rows, data.len(), "rows have different lengths");
+        Matrix { rows: n_rows, cols, data }
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::new(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                t[(c, r)] = self[(r, c)].clone();
+            }
+        }
+        t
+    }
+
+    pub fn mul_vec(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(v.len(), self.cols);
+        (0..self.rows)
+            .map(|r| self.row(r).iter().zip(v).fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone()))
+            .collect()
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for c in 0..self.cols {
+            self.data.swap(a * self.cols + c, b * self.cols + c);
+        }
+    }
+
+    // Gauss-Jordan elimination on the first `limit` columns, returning the pivot columns
+    fn eliminate(&mut self, limit: usize) -> Vec<usize> {
+        let mut pivots = vec![];
+        for c in 0..limit {
+            let r = pivots.len();
+            let Some(p) = (r..self.rows).find(|&p| !self[(p, c)].is_zero()) else { continue };
+            if p != r {
+                self.swap_rows(p, r);
+            }
+            let lead = self[(r, c)].clone();
+            for k in c..self.cols {
+                self[(r, k)] = self[(r, k)].clone() / lead.clone();
+            }
+            for other in 0..self.rows {
+                let factor = self[(other, c)].clone();
+                if other == r || factor.is_zero() {
+                    continue;
+                }
+                for k in c..self.cols {
+                    self[(other, k)] = self[(other, k)].clone() - factor.clone() * self[(r, k)].clone();
+                }
+            }
+            pivots.push(c);
+            if pivots.len() == self.rows {
+                break;
+            }
+        }
+        pivots
+    }
+
+    // reduced row echelon form, plus the pivot column of each nonzero row
+    pub fn rref(&self) -> (Self, Vec<usize>) {
+        let mut m = self.clone();
+        let pivots = m.eliminate(m.cols);
+        (m, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rref().1.len()
+    }
+
+    pub fn determinant(&self) -> T {
+        assert_eq!(self.rows, self.cols, "determinant of a non-square matrix");
+        // forward elimination only: the determinant is the product of the pivots, negated for
+        // each row swap
+        let mut m = self.clone();
+        let mut det = T::one();
+        for c in 0..m.cols {
+            let Some(p) = (c..m.rows).find(|&p| !m[(p, c)].is_zero()) else { return T::zero() };
+            if p != c {
+                m.swap_rows(p, c);
+                det = T::zero() - det;
+            }
+            let lead = m[(c, c)].clone();
+            det = det * lead.clone();
+            for r in c + 1..m.rows {
+                let factor = m[(r, c)].clone() / lead.clone();
+                for k in c..m.cols {
+                    m[(r, k)] = m[(r, k)].clone() - factor.clone() * m[(c, k)].clone();
+                }
+            }
+        }
+        det
+    }
+
+    // Some x with self * x = b, or None if there isn't one. Free variables are set to zero, so if
+    // the system is underdetermined the other solutions are this plus anything in the nullspace.
+    pub fn solve(&self, b: &[T]) -> Option<Vec<T>> {
+        assert_eq!(b.len(), self.rows);
+        let mut aug = Self::new(self.rows, self.cols + 1);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                aug[(r, c)] = self[(r, c)].clone();
+            }
+            aug[(r, self.cols)] = b[r].clone();
+        }
+        let pivots = aug.eliminate(self.cols);
+        if (pivots.len()..aug.rows).any(|r| !aug[(r, self.cols)].is_zero()) {
+            return None;
+        }
+        let mut x = vec![T::zero(); self.cols];
+        for (r, &c) in pivots.iter().enumerate() {
+            x[c] = aug[(r, self.cols)].clone();
+        }
+        Some(x)
+    }
+
+    // a basis for { x : self * x = 0 }
+    pub fn nullspace(&self) -> Vec<Vec<T>> {
+        let (m, pivots) = self.rref();
+        (0..self.cols).filter(|c| !pivots.contains(c)).map(|free| {
+            let mut v = vec![T::zero(); self.cols];
+            v[free] = T::one();
+            for (r, &c) in pivots.iter().enumerate() {
+                v[c] = T::zero() - m[(r, free)].clone();
+            }
+            v
+        }).collect()
+    }
+}
+
+impl Matrix<BigRational> {
+    pub fn from_int_rows<I, R>(rows: impl IntoIterator<Item = R>) -> Self
+        where I: Into<BigInt>, R: IntoIterator<Item = I>
+    {
+        Self::from_rows(rows.into_iter().map(|row| row.into_iter().map(|v| BigRational::from(v.into()))))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        &mut self.data[r * self.cols + c]
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter};
+use num::{BigInt, CheckedMul, Integer, Signed};
+
+// Returns (g, x, y) with a*x + b*y = g and g = gcd(a, b) >= 0.
+pub fn egcd<T: Integer + Signed + Clone>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (T::one(), T::zero());
+    let (mut t0, mut t1) = (T::zero(), T::one());
+    while !r1.is_zero() {
+        let q = r0.clone() / r1.clone();
+        (r0, r1) = (r1.clone(), r0 - q.clone() * r1);
+        (s0, s1) = (s1.clone(), s0 - q.clone() * s1);
+        (t0, t1) = (t1.clone(), t0 - q * t1);
+    }
+    if r0.is_negative() {
+        (-r0, -s0, -t0)
+    } else {
+        (r0, s0, t0)
+    }
+}
+
+// The inverse of a modulo m in 0..m, if a and m are coprime.
+pub fn modinv<T: Integer + Signed + Clone>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = egcd(a.mod_floor(&m), m.clone());
+    g.is_one().then(|| x.mod_floor(&m))
+}
+
+fn checked_lcm2<T: Integer + CheckedMul + Clone>(a: &T, b: &T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        return Some(T::zero());
+    }
+    (a.clone() / a.gcd(b)).checked_mul(b).map(|l| if l < T::zero() { T::zero() - l } else { l })
+}
+
+// lcm of everything in the iterator (1 if it's empty), or None if it overflows T
+pub fn checked_lcm<T, I>(values: I) -> Option<T>
+    where T: Integer + CheckedMul + Clone, I: IntoIterator<Item = T>
+{
+    values.into_iter().try_fold(T::one(), |acc, v| checked_lcm2(&acc, &v))
+}
+
+// A result which is kept in T for as long as it fits and promoted to a BigInt once it doesn't.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Wide<T> {
+    Fits(T),
+    Big(BigInt),
+}
+
+impl<T: Into<BigInt>> Wide<T> {
+    pub fn into_big(self) -> BigInt {
+        match self {
+            Wide::Fits(v) => v.into(),
+            Wide::Big(v) => v,
+        }
+    }
+
+    pub fn fits(self) -> Option<T> {
+        match self {
+            Wide::Fits(v) => Some(v),
+            Wide::Big(_) => None,
+        }
+    }
+}
+
+impl<T: Display> Display for Wide<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Wide::Fits(v) => v.fmt(f),
+            Wide::Big(v) => v.fmt(f),
+        }
+    }
+}
+
+// Like checked_lcm, but carries on in a BigInt instead of giving up on overflow.
+pub fn lcm_wide<T, I>(values: I) -> Wide<T>
+    where T: Integer + CheckedMul + Clone + Into<BigInt>, I: IntoIterator<Item = T>
+{
+    let mut values = values.into_iter();
+    let mut acc = T::one();
+    for v in values.by_ref() {
+        match checked_lcm2(&acc, &v) {
+            Some(l) => acc = l,
+            None => {
+                let big = acc.into().lcm(&v.into());
+                return Wide::Big(values.fold(big, |acc, v| acc.lcm(&v.into())));
+            }
+        }
+    }
+    Wide::Fits(acc)
+}
+
+// Solves x = r (mod m) for every (r, m) pair, where the moduli don't have to be coprime. Returns
+// (x, lcm of the moduli) with x in 0..lcm, or None if the congruences contradict each other. A
+// modulus of 0 pins x down to exactly r, in which case the result is (x, 0).
+// Intermediate values stay below the lcm squared, so pass BigInts if that might overflow T.
+pub fn crt<T, I>(congruences: I) -> Option<(T, T)>
+    where T: Integer + Signed + Clone, I: IntoIterator<Item = (T, T)>
+{
+    // whether exact satisfies x = r (mod m)
+    let agrees = |exact: &T, r: &T, m: &T| {
+        let diff = exact.clone() - r.clone();
+        if m.is_zero() { diff.is_zero() } else { diff.mod_floor(m).is_zero() }
+    };
+    congruences.into_iter().try_fold((T::zero(), T::one()), |(r1, m1), (r2, m2)| {
+        let m2 = m2.abs();
+        if m2.is_zero() {
+            return agrees(&r2, &r1, &m1).then_some((r2, m2));
+        }
+        if m1.is_zero() {
+            return agrees(&r1, &r2, &m2).then_some((r1, m1));
+        }
+        let (g, p, _) = egcd(m1.clone(), m2.clone());
+        let diff = r2 - r1.clone();
+        if !diff.is_multiple_of(&g) {
+            return None;
+        }
+        let step = m2 / g.clone();
+        let t = (diff / g).mod_floor(&step) * p.mod_floor(&step) % step.clone();
+        let lcm = m1.clone() * step;
+        Some(((r1 + m1 * t).mod_floor(&lcm), lcm))
+    })
+}
@@ -0,0 +1,134 @@
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use rustc_hash::FxHashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+struct Store<K, V> {
+    values: FxHashMap<K, V>,
+    // insertion order, only kept when there's a capacity to enforce
+    order: VecDeque<K>,
+}
+
+// A cache of computed values which can be shared between threads. The lock isn't held while a
+// value is being computed, so the computation is free to recurse back into the same memo (and two
+// threads might occasionally both compute the same value).
+pub struct Memo<K, V> {
+    store: Mutex<Store<K, V>>,
+    capacity: Option<usize>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            store: Mutex::new(Store { values: FxHashMap::default(), order: VecDeque::new() }),
+            capacity: None,
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    // once full, the oldest entries are forgotten to make room
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0, "memo capacity must be positive");
+        Memo { capacity: Some(capacity), ..Self::new() }
+    }
+
+    // The cached value for key, or f's result (which is then cached). Lookups can use a borrowed
+    // form of the key, so e.g. a Memo<String, _> can be queried with a &str without allocating.
+    pub fn get_or_insert_with<Q>(&self, key: &Q, f: impl FnOnce() -> V) -> V
+        where K: Borrow<Q>, Q: Hash + Eq + ToOwned<Owned = K> + ?Sized
+    {
+        if let Some(value) = self.store.lock().unwrap().values.get(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return value.clone();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = f();
+        self.insert(key.to_owned(), value.clone());
+        value
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.store.lock().unwrap().values.get(key).cloned()
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut store = self.store.lock().unwrap();
+        let Some(capacity) = self.capacity else {
+            store.values.insert(key, value);
+            return;
+        };
+        if store.values.insert(key.clone(), value).is_none() {
+            store.order.push_back(key);
+            while store.order.len() > capacity {
+                let oldest = store.order.pop_front().unwrap();
+                store.values.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.lock().unwrap().values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut store = self.store.lock().unwrap();
+        store.values.clear();
+        store.order.clear();
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats { hits: self.hits.load(Ordering::Relaxed), misses: self.misses.load(Ordering::Relaxed) }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A recursive function with its own memo. f gets called with something to recurse through (which
+// goes via the cache) and the argument.
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+    where K: Clone + Eq + Hash, V: Clone, F: Fn(&dyn Fn(&K) -> V, &K) -> V
+{
+    pub fn new(f: F) -> Self {
+        Memoized { memo: Memo::new(), f }
+    }
+
+    pub fn bounded(capacity: usize, f: F) -> Self {
+        Memoized { memo: Memo::bounded(capacity), f }
+    }
+
+    pub fn call(&self, key: &K) -> V {
+        self.memo.get_or_insert_with(key, || (self.f)(&|k| self.call(k), key))
+    }
+
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.memo.stats()
+    }
+}
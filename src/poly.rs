@@ -0,0 +1,98 @@
+use num::{Integer, Num};
+use num::rational::Ratio;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PolyErr {
+    #[error("no polynomial of degree {max_degree} or less fits the samples")]
+    NoFit { max_degree: usize },
+    #[error("need at least {needed} samples to confirm the degree")]
+    TooFewSamples { needed: usize },
+    #[error("two points share an x value")]
+    DuplicateX,
+}
+
+// A polynomial given by its Newton forward differences at 0, i.e. diffs[k] = Δ^k f(0), where the
+// samples it came from were f(0), f(1), f(2), ...
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Newton<T> {
+    diffs: Vec<T>,
+}
+
+fn differences<T: Clone + Num>(row: &[T]) -> Vec<T> {
+    row.windows(2).map(|w| w[1].clone() - w[0].clone()).collect()
+}
+
+impl<T: Clone + Num> Newton<T> {
+    // the polynomial of degree < samples.len() through every sample
+    pub fn through(samples: &[T]) -> Self {
+        let mut diffs = vec![];
+        let mut row = samples.to_vec();
+        while let Some(first) = row.first() {
+            diffs.push(first.clone());
+            row = differences(&row);
+        }
+        Newton { diffs }
+    }
+
+    // The lowest degree polynomial through the samples, as long as that's at most max_degree. A
+    // degree d fit only counts once there's a spare sample to check it against, so this needs
+    // d + 2 samples.
+    pub fn fit(samples: &[T], max_degree: usize) -> Result<Self, PolyErr> {
+        let mut diffs = vec![];
+        let mut row = samples.to_vec();
+        for degree in 0..=max_degree {
+            if row.len() < 2 {
+                return Err(PolyErr::TooFewSamples { needed: degree + 2 });
+            }
+            diffs.push(row[0].clone());
+            row = differences(&row);
+            if row.iter().all(|v| v.is_zero()) {
+                return Ok(Newton { diffs });
+            }
+        }
+        Err(PolyErr::NoFit { max_degree })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.diffs.iter().rposition(|d| !d.is_zero()).unwrap_or(0)
+    }
+
+    pub fn differences(&self) -> &[T] {
+        &self.diffs
+    }
+
+    // f(x) = sum of Δ^k f(0) * binomial(x, k), which is fine for any x, negative or past the end.
+    // For integer T each binomial step divides exactly.
+    pub fn eval(&self, x: T) -> T {
+        let mut total = T::zero();
+        let mut binomial = T::one();
+        let mut k = T::zero();
+        for d in &self.diffs {
+            total = total + d.clone() * binomial.clone();
+            binomial = binomial * (x.clone() - k.clone());
+            k = k + T::one();
+            binomial = binomial / k.clone();
+        }
+        total
+    }
+}
+
+// The value at x of the polynomial through the given points, which can be spaced however. Exact,
+// so the result is only an integer if the polynomial happens to land on one.
+pub fn lagrange<T: Clone + Integer>(points: &[(T, T)], x: T) -> Result<Ratio<T>, PolyErr> {
+    let mut total = Ratio::from_integer(T::zero());
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        let mut term = Ratio::from_integer(yi.clone());
+        for (j, (xj, _)) in points.iter().enumerate() {
+            if i != j {
+                if xi == xj {
+                    return Err(PolyErr::DuplicateX);
+                }
+                term = term * Ratio::new(x.clone() - xj.clone(), xi.clone() - xj.clone());
+            }
+        }
+        total = total + term;
+    }
+    Ok(total)
+}
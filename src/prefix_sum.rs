@@ -0,0 +1,92 @@
+use std::ops::{Add, Sub};
+use crate::bitgrid::BitGrid;
+use crate::coord::{PointSet, Pt, Point};
+use crate::grid::Grid;
+
+// Summed-area table: after an O(width * height) build, the sum of any rectangle is four lookups.
+// Stored with an extra zero row and column so that queries don't need special cases at the edges.
+#[derive(Clone, Debug)]
+pub struct PrefixSum2D<T> {
+    pub width: usize,
+    pub height: usize,
+    // sums[y][x] is the sum of everything above and to the left of (x, y), exclusive
+    sums: Grid<T>,
+}
+
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> PrefixSum2D<T> {
+    pub fn new(grid: &Grid<T>) -> Self {
+        let mut sums = Grid::new(grid.width + 1, grid.height + 1);
+        for (y, row) in grid.rows().enumerate() {
+            let mut row_sum = T::default();
+            for (x, value) in row.iter().enumerate() {
+                row_sum = row_sum + *value;
+                sums[Pt(x + 1, y + 1)] = sums[Pt(x + 1, y)] + row_sum;
+            }
+        }
+        PrefixSum2D { width: grid.width, height: grid.height, sums }
+    }
+
+    // Sum of the rectangle from top_left to bottom_right (exclusive), like Grid::subgrid
+    #[inline]
+    pub fn sum(&self, top_left: Pt<usize>, bottom_right: Pt<usize>) -> T {
+        let (Point([x1, y1]), Point([x2, y2])) = (top_left, bottom_right);
+        if x2 > self.width || y2 > self.height {
+            panic!("{} is out of bounds for {}x{} PrefixSum2D", bottom_right, self.width, self.height);
+        }
+        if x1 >= x2 || y1 >= y2 {
+            return T::default();
+        }
+        self.sums[Pt(x2, y2)] + self.sums[Pt(x1, y1)] - self.sums[Pt(x1, y2)] - self.sums[Pt(x2, y1)]
+    }
+
+    pub fn total(&self) -> T {
+        self.sums[Pt(self.width, self.height)]
+    }
+
+    // sum of row y up to (not including) x
+    pub fn row_prefix(&self, y: usize, x: usize) -> T {
+        self.sum(Pt(0, y), Pt(x, y + 1))
+    }
+
+    // sum of column x up to (not including) y
+    pub fn column_prefix(&self, x: usize, y: usize) -> T {
+        self.sum(Pt(x, 0), Pt(x + 1, y))
+    }
+
+    pub fn row_sum(&self, y: usize) -> T {
+        self.row_prefix(y, self.width)
+    }
+
+    pub fn column_sum(&self, x: usize) -> T {
+        self.column_prefix(x, self.height)
+    }
+
+    // sum of every row above y
+    pub fn rows_before(&self, y: usize) -> T {
+        self.sum(Pt(0, 0), Pt(self.width, y))
+    }
+
+    // sum of every column left of x
+    pub fn columns_before(&self, x: usize) -> T {
+        self.sum(Pt(0, 0), Pt(x, self.height))
+    }
+}
+
+// Counting versions: each set point contributes 1
+
+impl From<&BitGrid> for PrefixSum2D<usize> {
+    fn from(bits: &BitGrid) -> Self {
+        let mut counts = Grid::new(bits.width, bits.height);
+        for p in bits.iter_ones() {
+            counts[p] = 1;
+        }
+        PrefixSum2D::new(&counts)
+    }
+}
+
+impl PrefixSum2D<usize> {
+    // PointSets don't know their own height
+    pub fn from_points(points: &PointSet<usize>, height: usize) -> Self {
+        PrefixSum2D::new(&points.as_grid(height).map(|b| *b as usize))
+    }
+}
@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+use rustc_hash::FxHashMap;
+
+// Somewhere to keep the best known cost (and optionally the predecessor) of each state. Use
+// IndexStore when states can be cheaply mapped to a dense index, HashStore otherwise.
+pub trait Store<S, C> {
+    fn best(&self, state: &S) -> Option<C>;
+    fn set(&mut self, state: &S, cost: C, prev: Option<&S>);
+    fn prev(&self, state: &S) -> Option<S>;
+    fn tracks_paths(&self) -> bool;
+}
+
+pub struct HashStore<S, C> {
+    best: FxHashMap<S, (C, Option<S>)>,
+    paths: bool,
+}
+
+impl<S, C> HashStore<S, C> {
+    pub fn new() -> Self {
+        HashStore { best: FxHashMap::default(), paths: false }
+    }
+
+    pub fn with_paths() -> Self {
+        HashStore { best: FxHashMap::default(), paths: true }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&S, &C)> {
+        self.best.iter().map(|(s, (c, _))| (s, c))
+    }
+
+    pub fn len(&self) -> usize {
+        self.best.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.best.is_empty()
+    }
+}
+
+impl<S, C> Default for HashStore<S, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> Store<S, C> for HashStore<S, C> {
+    #[inline]
+    fn best(&self, state: &S) -> Option<C> {
+        self.best.get(state).map(|(c, _)| *c)
+    }
+
+    #[inline]
+    fn set(&mut self, state: &S, cost: C, prev: Option<&S>) {
+        let prev = if self.paths { prev.cloned() } else { None };
+        self.best.insert(state.clone(), (cost, prev));
+    }
+
+    fn prev(&self, state: &S) -> Option<S> {
+        self.best.get(state).and_then(|(_, p)| p.clone())
+    }
+
+    fn tracks_paths(&self) -> bool {
+        self.paths
+    }
+}
+
+pub struct IndexStore<S, C, F> {
+    index: F,
+    best: Vec<Option<C>>,
+    prev: Vec<Option<S>>,
+}
+
+impl<S: Clone, C: Clone, F: Fn(&S) -> usize> IndexStore<S, C, F> {
+    // every state must map to an index less than size
+    pub fn new(size: usize, index: F) -> Self {
+        IndexStore { index, best: vec![None; size], prev: vec![] }
+    }
+
+    pub fn with_paths(size: usize, index: F) -> Self {
+        IndexStore { index, best: vec![None; size], prev: vec![None; size] }
+    }
+}
+
+impl<S: Clone, C: Copy, F: Fn(&S) -> usize> Store<S, C> for IndexStore<S, C, F> {
+    #[inline]
+    fn best(&self, state: &S) -> Option<C> {
+        self.best[(self.index)(state)]
+    }
+
+    #[inline]
+    fn set(&mut self, state: &S, cost: C, prev: Option<&S>) {
+        let i = (self.index)(state);
+        self.best[i] = Some(cost);
+        if !self.prev.is_empty() {
+            self.prev[i] = prev.cloned();
+        }
+    }
+
+    fn prev(&self, state: &S) -> Option<S> {
+        self.prev.get((self.index)(state)).cloned().flatten()
+    }
+
+    fn tracks_paths(&self) -> bool {
+        !self.prev.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Found<S, C> {
+    pub cost: C,
+    pub state: S,
+    // start to finish inclusive, if the store was tracking paths
+    pub path: Option<Vec<S>>,
+}
+
+impl<S: Clone, C> Found<S, C> {
+    fn from_store(store: &impl Store<S, C>, state: S, cost: C) -> Self {
+        let path = if store.tracks_paths() {
+            let mut path = vec![state.clone()];
+            while let Some(prev) = store.prev(path.last().unwrap()) {
+                path.push(prev);
+            }
+            path.reverse();
+            Some(path)
+        } else {
+            None
+        };
+        Found { cost, state, path }
+    }
+}
+
+#[inline]
+fn improves<C: Ord>(best: Option<C>, cost: C) -> bool {
+    match best {
+        Some(best) => cost < best,
+        None => true,
+    }
+}
+
+struct Queued<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Queued<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // flipped because Heap is a max-heap
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Queued<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Queued<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Queued<S, C> {}
+
+// Unweighted search. Costs are numbers of steps. If no goal is found, the store is left holding the
+// distance to everything reachable.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    store: &mut impl Store<S, usize>,
+) -> Option<Found<S, usize>>
+where
+    S: Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut queue = VecDeque::new();
+    for start in starts {
+        store.set(&start, 0, None);
+        queue.push_back((start, 0));
+    }
+
+    while let Some((state, cost)) = queue.pop_front() {
+        if is_goal(&state) {
+            return Some(Found::from_store(store, state, cost));
+        }
+        for next in successors(&state) {
+            if store.best(&next).is_none() {
+                store.set(&next, cost + 1, Some(&state));
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+    store: &mut impl Store<S, C>,
+) -> Option<Found<S, C>>
+where
+    S: Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal, store)
+}
+
+// The heuristic must never overestimate the remaining cost, or the result may not be optimal.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+    store: &mut impl Store<S, C>,
+) -> Option<Found<S, C>>
+where
+    S: Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        store.set(&start, C::default(), None);
+        queue.push(Queued { priority: heuristic(&start), cost: C::default(), state: start });
+    }
+
+    while let Some(Queued { cost, state, .. }) = queue.pop() {
+        if store.best(&state).is_some_and(|best| best < cost) {
+            // stale entry; we found a cheaper way here after queueing this one
+            continue;
+        }
+        if is_goal(&state) {
+            return Some(Found::from_store(store, state, cost));
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            if improves(store.best(&next), next_cost) {
+                store.set(&next, next_cost, Some(&state));
+                queue.push(Queued { priority: next_cost + heuristic(&next), cost: next_cost, state: next });
+            }
+        }
+    }
+    None
+}
+
+// Dijkstra with a bucket queue (Dial's algorithm): much faster than a heap when edge costs are
+// small integers.
+pub fn bucket_dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    store: &mut impl Store<S, usize>,
+) -> Option<Found<S, usize>>
+where
+    S: Clone,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut buckets: Vec<Vec<S>> = vec![vec![]];
+    for start in starts {
+        store.set(&start, 0, None);
+        buckets[0].push(start);
+    }
+
+    let mut cost = 0;
+    while cost < buckets.len() {
+        while let Some(state) = buckets[cost].pop() {
+            if store.best(&state).is_some_and(|best| best < cost) {
+                continue;
+            }
+            if is_goal(&state) {
+                return Some(Found::from_store(store, state, cost));
+            }
+            for (next, step) in successors(&state) {
+                let next_cost = cost + step;
+                if improves(store.best(&next), next_cost) {
+                    store.set(&next, next_cost, Some(&state));
+                    if next_cost >= buckets.len() {
+                        buckets.resize_with(next_cost + 1, Vec::new);
+                    }
+                    buckets[next_cost].push(next);
+                }
+            }
+        }
+        cost += 1;
+    }
+    None
+}